@@ -1,28 +1,51 @@
 use crate::{
-    loader::halo2::Halo2Loader,
-    protocol::Protocol,
-    scheme::{AccumulationScheme, ShplonkAccumulationScheme},
-    util::{Curve, Group},
+    loader::{
+        halo2::Halo2Loader,
+        native::{NativeLoader, PoseidonTranscript as NativePoseidonTranscript},
+        CostMetering,
+    },
+    protocol::{compile, digest_protocols, Protocol},
+    scheme::MultiOpenScheme,
+    util::{fe_to_limbs, Curve, Group, PrimeCurveAffine, PrimeField},
 };
-use halo2_wrong::curves::pairing::Engine;
+use halo2_wrong::curves::{pairing::Engine, CurveAffine};
 use halo2_wrong::halo2::{
 	arithmetic::FieldExt,
     circuit::{floor_planner::V1, Layouter, Value},
-    plonk::{Circuit, ConstraintSystem, Error},
+    plonk::{Circuit, ConstraintSystem, Error, VerifyingKey},
+    poly::kzg::commitment::ParamsKZG,
 };
 use halo2_wrong_transcript::NativeRepresentation;
 use halo2_wrong_ecc::BaseFieldEccChip;
 use halo2_wrong_maingate::{RegionCtx, RangeInstructions};
 use std::vec;
 use crate::native::Snark;
-use halo2_wrong_maingate::{MainGateConfig, MainGate, RangeConfig, RangeChip};
+use halo2_wrong_maingate::{
+    MainGateConfig, MainGate, MainGateInstructions, RangeConfig, RangeChip,
+};
 use halo2_wrong_ecc::EccConfig;
 use crate::scheme::SameCurveAccumulation;
 use crate::loader::halo2::PoseidonTranscript;
 
+/// Default limb layout for BN254, `4 * 68` bits covering its 254-bit base field.
 pub const LIMBS: usize = 4;
 pub const BITS: usize = 68;
 
+/// Returns [`crate::Error::InvalidLimbLayout`] unless `LIMBS` limbs of `BITS`
+/// bits cover the base field and each fits into the scalar field.
+fn check_limb_layout<C: CurveAffine, const LIMBS: usize, const BITS: usize>(
+) -> Result<(), crate::Error> {
+    if LIMBS * BITS < <C::Base as PrimeField>::NUM_BITS as usize
+        || BITS >= <C::Scalar as PrimeField>::NUM_BITS as usize
+    {
+        return Err(crate::Error::InvalidLimbLayout {
+            limbs: LIMBS,
+            bits: BITS,
+        });
+    }
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct AccumulatorConfig {
     main_gate_config: MainGateConfig,
@@ -44,6 +67,7 @@ impl AccumulatorConfig {
 
 pub struct SnarkWitness<C: Curve> {
     protocol: Protocol<C>,
+    scheme: MultiOpenScheme,
     statements: Vec<Vec<Value<<C as Group>::Scalar>>>,
     proof: Value<Vec<u8>>,
 }
@@ -52,6 +76,7 @@ impl<C: Curve> From<Snark<C>> for SnarkWitness<C> {
     fn from(snark: Snark<C>) -> Self {
         Self {
             protocol: snark.protocol,
+            scheme: snark.scheme,
             statements: snark
                 .statements
                 .into_iter()
@@ -66,6 +91,7 @@ impl<C: Curve> SnarkWitness<C> {
     pub fn without_witnesses(&self) -> Self {
         SnarkWitness {
             protocol: self.protocol.clone(),
+            scheme: self.scheme,
             statements: self
                 .statements
                 .iter()
@@ -76,12 +102,217 @@ impl<C: Curve> SnarkWitness<C> {
     }
 }
 
-pub struct Accumulator<E: Engine> {
+/// Positions of the accumulator limbs exposed by [`Accumulator`], all in the
+/// first `4 * LIMBS` rows of instance column 0.
+pub fn accumulator_indices<const LIMBS: usize>() -> Vec<(usize, usize)> {
+    (0..4 * LIMBS).map(|row| (0, row)).collect()
+}
+
+/// Compiles the verifying key of an [`Accumulator`] circuit into a protocol
+/// carrying its accumulator, so its proofs can be aggregated again.
+pub fn compile_accumulator<C: CurveAffine, const LIMBS: usize>(
+    vk: &VerifyingKey<C>,
+    n: usize,
+) -> Protocol<C::CurveExt> {
+    compile(vk, n, Some(accumulator_indices::<LIMBS>()))
+}
+
+/// Runs the same accumulation as [`Accumulator`] with [`NativeLoader`] and
+/// returns the `4 * LIMBS` public instances in the order the circuit exposes
+/// them: limbs of `lhs.x`, `lhs.y`, `rhs.x` and then `rhs.y`.
+pub fn accumulator_instances<E: Engine, const LIMBS: usize, const BITS: usize>(
+    g1: E::G1Affine,
+    snarks: &[Snark<E::G1>],
+) -> Result<Vec<E::Scalar>, crate::Error> {
+    check_limb_layout::<E::G1Affine, LIMBS, BITS>()?;
+
+    let mut strategy = SameCurveAccumulation::<_, NativeLoader, LIMBS, BITS>::default();
+    for snark in snarks.iter() {
+        let mut transcript =
+            NativePoseidonTranscript::<E::G1Affine, _>::new(snark.proof.as_slice());
+        snark.scheme.accumulate(
+            &snark.protocol,
+            &NativeLoader,
+            snark.statements.clone(),
+            &mut transcript,
+            &mut strategy,
+        )?;
+    }
+    let (lhs, rhs) = strategy.finalize(g1.to_curve())?;
+
+    let coordinates = [lhs, rhs]
+        .into_iter()
+        .map(|ec_point| {
+            let coordinates = ec_point
+                .to_affine()
+                .coordinates()
+                .map(|coordinates| [*coordinates.x(), *coordinates.y()]);
+            Option::<[_; 2]>::from(coordinates).ok_or(crate::Error::IdentityAccumulator)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(coordinates
+        .into_iter()
+        .flatten()
+        .flat_map(fe_to_limbs::<_, _, LIMBS, BITS>)
+        .collect())
+}
+
+pub struct Accumulator<E: Engine, const LIMBS: usize, const BITS: usize> {
     g1: E::G1Affine,
     snarks: Vec<SnarkWitness<E::G1>>,
+    protocol_digest: Option<E::Scalar>,
 }
 
-impl<E: Engine> Circuit<E::Scalar> for Accumulator<E> {
+impl<E: Engine, const LIMBS: usize, const BITS: usize> Accumulator<E, LIMBS, BITS> {
+    /// Creates the aggregation circuit for `snarks` together with the public
+    /// instances it exposes, the limbs of the accumulated `lhs` and `rhs`.
+    pub fn new(
+        params: &ParamsKZG<E>,
+        snarks: Vec<Snark<E::G1>>,
+    ) -> Result<(Self, Vec<E::Scalar>), crate::Error> {
+        let g1 = params.get_g()[0];
+        let instances = accumulator_instances::<E, LIMBS, BITS>(g1, &snarks)?;
+        let circuit = Self {
+            g1,
+            snarks: snarks.into_iter().map(SnarkWitness::from).collect(),
+            protocol_digest: None,
+        };
+        Ok((circuit, instances))
+    }
+
+    /// Like [`Accumulator::new`], but additionally exposes
+    /// [`digest_protocols`] of the snarks' protocols after the accumulator
+    /// limbs, binding the aggregated proof to those verifying keys.
+    pub fn new_with_protocol_digest(
+        params: &ParamsKZG<E>,
+        snarks: Vec<Snark<E::G1>>,
+    ) -> Result<(Self, Vec<E::Scalar>), crate::Error> {
+        let protocol_digest = digest_protocols(snarks.iter().map(|snark| &snark.protocol));
+        let (mut circuit, mut instances) = Self::new(params, snarks)?;
+        circuit.protocol_digest = Some(protocol_digest);
+        instances.push(protocol_digest);
+        Ok((circuit, instances))
+    }
+
+    pub fn num_instance(&self) -> usize {
+        4 * LIMBS + usize::from(self.protocol_digest.is_some())
+    }
+
+    /// Like [`Circuit::synthesize`], but returns the rows taken by each
+    /// metered section, which are `transcript_read`, `common_poly_evaluation`
+    /// and `pairing_prep` for each snark in order, prefixed by
+    /// `snark {index}/`, followed by the final `msm`.
+    pub fn synthesize_with_row_meterings(
+        &self,
+        config: AccumulatorConfig,
+        mut layouter: impl Layouter<E::Scalar>,
+    ) -> Result<Vec<(String, usize)>, Error> {
+        check_limb_layout::<E::G1Affine, LIMBS, BITS>()?;
+        config.load_table(&mut layouter)?;
+
+        // Each snark is accumulated in its own region, carrying the accumulator
+        // over to the next one, so the floor planner can lay them out freely.
+        // The loader is carried over too, so the aux generator, aux points and
+        // constant points are only assigned once
+        let mut row_meterings = vec![Vec::new(); self.snarks.len() + 1];
+        let mut accumulator = None;
+        let mut detached_loader = None;
+        for (idx, snark) in self.snarks.iter().enumerate() {
+            (accumulator, detached_loader) = layouter.assign_region(
+                || format!("snark {}", idx),
+                |mut region| {
+                    let mut offset = 0;
+                    let ctx = RegionCtx::new(&mut region, &mut offset);
+
+                    let loader = Halo2Loader::<E::G1Affine, LIMBS, BITS>::attach(
+                        config.ecc_config(),
+                        ctx,
+                        detached_loader.clone(),
+                    );
+                    let mut strategy = SameCurveAccumulation::attach(&loader, accumulator.clone());
+                    let mut transcript = PoseidonTranscript::<
+                        E::G1Affine,
+                        _,
+                        NativeRepresentation,
+                        LIMBS,
+                        BITS,
+                    >::new(
+                        &loader,
+                        snark.proof.as_ref().map(|proof| proof.as_slice()),
+                    );
+                    let statements = snark
+                        .statements
+                        .iter()
+                        .map(|statements| {
+                            statements
+                                .iter()
+                                .map(|statement| loader.assign_scalar(*statement))
+                                .collect::<Vec<_>>()
+                        })
+                        .collect::<Vec<_>>();
+                    snark.scheme.accumulate(
+                        &snark.protocol,
+                        &loader,
+                        statements,
+                        &mut transcript,
+                        &mut strategy,
+                    )?;
+                    loader.take_error()?;
+                    row_meterings[idx] = loader
+                        .row_meterings()
+                        .into_iter()
+                        .map(|(identifier, rows)| (format!("snark {}/{}", idx, identifier), rows))
+                        .collect();
+
+                    Ok((strategy.detach(), Some(loader.detach())))
+                },
+            )?;
+        }
+
+        let (lhs, rhs, protocol_digest) = layouter.assign_region(
+            || "msm",
+            |mut region| {
+                let mut offset = 0;
+                let ctx = RegionCtx::new(&mut region, &mut offset);
+
+                let loader = Halo2Loader::<E::G1Affine, LIMBS, BITS>::attach(
+                    config.ecc_config(),
+                    ctx,
+                    detached_loader.clone(),
+                );
+                let strategy = SameCurveAccumulation::attach(&loader, accumulator.clone());
+                let (lhs, rhs) = {
+                    let _msm = CostMetering::<<E::G1Affine as CurveAffine>::CurveExt, _>::start(
+                        &loader, "msm",
+                    );
+                    strategy.finalize(self.g1)?
+                };
+                row_meterings[self.snarks.len()] = loader.row_meterings();
+                // Assigned as constant, so the digest is fixed by the verifying key
+                let protocol_digest = self.protocol_digest.map(|protocol_digest| {
+                    loader.assign_const_scalar(protocol_digest).assigned()
+                });
+
+                Ok((lhs, rhs, protocol_digest))
+            },
+        )?;
+
+        let ecc_chip = BaseFieldEccChip::<E::G1Affine, LIMBS, BITS>::new(config.ecc_config());
+        ecc_chip.expose_public(layouter.namespace(|| ""), lhs, 0)?;
+        ecc_chip.expose_public(layouter.namespace(|| ""), rhs, 2 * LIMBS)?;
+        if let Some(protocol_digest) = protocol_digest {
+            let main_gate = MainGate::<E::Scalar>::new(config.main_gate_config.clone());
+            main_gate.expose_public(layouter.namespace(|| ""), protocol_digest, 4 * LIMBS)?;
+        }
+
+        Ok(row_meterings.concat())
+    }
+}
+
+impl<E: Engine, const LIMBS: usize, const BITS: usize> Circuit<E::Scalar>
+    for Accumulator<E, LIMBS, BITS>
+{
     type Config = AccumulatorConfig;
     type FloorPlanner = V1;
 
@@ -93,6 +324,7 @@ impl<E: Engine> Circuit<E::Scalar> for Accumulator<E> {
                 .iter()
                 .map(SnarkWitness::without_witnesses)
                 .collect(),
+            protocol_digest: self.protocol_digest,
         }
     }
 
@@ -111,60 +343,331 @@ impl<E: Engine> Circuit<E::Scalar> for Accumulator<E> {
     fn synthesize(
         &self,
         config: Self::Config,
-        mut layouter: impl Layouter<E::Scalar>,
+        layouter: impl Layouter<E::Scalar>,
     ) -> Result<(), Error> {
-        config.load_table(&mut layouter)?;
+        self.synthesize_with_row_meterings(config, layouter).map(|_| ())
+    }
+}
 
-        let (lhs, rhs) = layouter.assign_region(
-            || "",
-            |mut region| {
-                let mut offset = 0;
-                let ctx = RegionCtx::new(&mut region, &mut offset);
+#[cfg(test)]
+mod test {
+    use super::{
+        accumulator_indices, accumulator_instances, compile_accumulator, Accumulator,
+        AccumulatorConfig, BITS, LIMBS,
+    };
+    use crate::{
+        loader::native::{ChallengeScalar, NativeLoader, PoseidonTranscript},
+        native::Snark,
+        protocol::digest_protocols,
+        scheme::{MultiOpenScheme, SameCurveAccumulation},
+        test::{app_params, app_snark, create_snarks, standard_plonk_snarks, Passthrough, APP_K},
+        util::{accumulate_snark, fe_from_limbs, Field, PrimeCurveAffine},
+    };
+    use halo2_wrong::curves::{
+        bn256::{Bn256, Fq, Fr, G1Affine, G1, G2Prepared},
+        pairing::{MillerLoopResult, MultiMillerLoop},
+        CurveAffine,
+    };
+    use halo2_wrong::halo2::{
+        circuit::{floor_planner::V1, Layouter},
+        dev::MockProver,
+        plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+        poly::{
+            commitment::{CommitmentScheme, Params},
+            kzg::{commitment::KZGCommitmentScheme, multiopen::ProverSHPLONK},
+        },
+    };
+    use rand::rngs::OsRng;
+    use std::{cell::RefCell, rc::Rc};
 
-                let loader = Halo2Loader::<E::G1Affine, LIMBS, BITS>::new(config.ecc_config(), ctx);
-                let mut stretagy = SameCurveAccumulation::default();
-                for snark in self.snarks.iter() {
-                    let mut transcript = PoseidonTranscript::<E::G1Affine, _, NativeRepresentation, LIMBS, BITS>::new(
-						&loader,
-						snark.proof.as_ref().map(|proof| proof.as_slice()),
-					);
-					let statements = snark
-						.statements
-						.iter()
-						.map(|statements| {
-							statements
-								.iter()
-								.map(|statement| loader.assign_scalar(*statement))
-								.collect::<Vec<_>>()
-						})
-						.collect::<Vec<_>>();
-					ShplonkAccumulationScheme::accumulate(
-						&snark.protocol,
-						&loader,
-						statements,
-						&mut transcript,
-						&mut stretagy,
-					)
-					.map_err(|_| Error::Synthesis)?;
-                }
-                let (lhs, rhs) = stretagy.finalize(self.g1);
-
-                Ok((lhs, rhs))
-            },
-        )?;
+    const K: u32 = 21;
 
-        let ecc_chip = BaseFieldEccChip::<E::G1Affine, LIMBS, BITS>::new(config.ecc_config());
-        ecc_chip.expose_public(layouter.namespace(|| ""), lhs, 0)?;
-        ecc_chip.expose_public(layouter.namespace(|| ""), rhs, 2 * LIMBS)?;
+    /// Synthesizes an [`Accumulator`], keeping the rows of its metered
+    /// sections.
+    struct RowMetered {
+        circuit: Accumulator<Bn256, LIMBS, BITS>,
+        row_meterings: Rc<RefCell<Vec<(String, usize)>>>,
+    }
 
-        Ok(())
+    impl Circuit<Fr> for RowMetered {
+        type Config = AccumulatorConfig;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                circuit: self.circuit.without_witnesses(),
+                row_meterings: self.row_meterings.clone(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            Accumulator::<Bn256, LIMBS, BITS>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            *self.row_meterings.borrow_mut() =
+                self.circuit.synthesize_with_row_meterings(config, layouter)?;
+            Ok(())
+        }
     }
-}
 
-#[cfg(test)]
-mod test {
-	#[test]
-	fn should_accumulate_two_proofs() {
-		
-	}
-}
\ No newline at end of file
+    fn accumulate_natively(
+        snarks: &[Snark<G1>],
+    ) -> SameCurveAccumulation<G1, NativeLoader, LIMBS, BITS> {
+        let mut strategy = SameCurveAccumulation::default();
+        for snark in snarks.iter() {
+            let mut transcript = PoseidonTranscript::<G1Affine, _>::new(snark.proof.as_slice());
+            snark.scheme.accumulate(
+                &snark.protocol,
+                &NativeLoader,
+                snark.statements.clone(),
+                &mut transcript,
+                &mut strategy,
+            )
+            .unwrap();
+        }
+        strategy
+    }
+
+    #[test]
+    fn should_accumulate_two_proofs() {
+        let params = app_params();
+        let snarks = standard_plonk_snarks::<PoseidonTranscript<_, _>>(
+            &params,
+            &[MultiOpenScheme::Shplonk; 2],
+        );
+        let g1 = params.get_g()[0];
+
+        assert!(accumulate_natively(&snarks)
+            .decide::<Bn256>(g1, params.g2(), params.s_g2())
+            .unwrap());
+
+        let (circuit, instances) =
+            Accumulator::<_, LIMBS, BITS>::new(&params, snarks).unwrap();
+        assert_eq!(instances.len(), circuit.num_instance());
+
+        let circuit = RowMetered {
+            circuit,
+            row_meterings: Rc::new(RefCell::new(Vec::new())),
+        };
+        MockProver::run(K, &circuit, vec![instances])
+            .unwrap()
+            .assert_satisfied();
+
+        let row_meterings = circuit.row_meterings.borrow().clone();
+        let sections = row_meterings
+            .iter()
+            .map(|(identifier, _)| identifier.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sections,
+            [
+                "snark 0/transcript_read",
+                "snark 0/common_poly_evaluation",
+                "snark 0/pairing_prep",
+                "snark 1/transcript_read",
+                "snark 1/common_poly_evaluation",
+                "snark 1/pairing_prep",
+                "msm",
+            ]
+        );
+        assert!(row_meterings.iter().all(|(_, rows)| *rows > 0));
+    }
+
+    /// Carries the accumulator and loader across more than one region, so
+    /// reused aux and constant points are copy constrained across regions,
+    /// and checks the exposed accumulator is the one computed in a single pass.
+    #[test]
+    fn should_accumulate_three_proofs_across_regions() {
+        let params = app_params();
+        let snarks = standard_plonk_snarks::<PoseidonTranscript<_, _>>(
+            &params,
+            &[MultiOpenScheme::Shplonk; 3],
+        );
+        let expected =
+            accumulator_instances::<Bn256, LIMBS, BITS>(params.get_g()[0], &snarks).unwrap();
+
+        let (circuit, _) = Accumulator::<_, LIMBS, BITS>::new(&params, snarks).unwrap();
+        // Takes more rows than two snarks do
+        MockProver::run(K + 1, &circuit, vec![expected.clone()])
+            .unwrap()
+            .assert_satisfied();
+
+        let mut tampered = expected;
+        tampered[0] += Fr::one();
+        assert!(MockProver::run(K + 1, &circuit, vec![tampered])
+            .unwrap()
+            .verify()
+            .is_err());
+    }
+
+    #[test]
+    fn should_accumulate_mixed_multi_open_proofs() {
+        let params = app_params();
+        let snarks = standard_plonk_snarks::<PoseidonTranscript<_, _>>(
+            &params,
+            &[MultiOpenScheme::Plonk, MultiOpenScheme::Shplonk],
+        );
+        let g1 = params.get_g()[0];
+
+        assert!(accumulate_natively(&snarks)
+            .decide::<Bn256>(g1, params.g2(), params.s_g2())
+            .unwrap());
+
+        let (circuit, instances) =
+            Accumulator::<_, LIMBS, BITS>::new(&params, snarks).unwrap();
+        MockProver::run(K, &circuit, vec![instances])
+            .unwrap()
+            .assert_satisfied();
+    }
+
+    #[test]
+    fn should_expose_protocol_digest() {
+        let params = app_params();
+        let snarks = standard_plonk_snarks::<PoseidonTranscript<_, _>>(
+            &params,
+            &[MultiOpenScheme::Shplonk; 2],
+        );
+        let protocol_digest = digest_protocols(snarks.iter().map(|snark| snark.protocol()));
+
+        let (circuit, mut instances) =
+            Accumulator::<_, LIMBS, BITS>::new_with_protocol_digest(&params, snarks).unwrap();
+        assert_eq!(instances.len(), circuit.num_instance());
+        assert_eq!(instances[4 * LIMBS], protocol_digest);
+
+        MockProver::run(K, &circuit, vec![instances.clone()])
+            .unwrap()
+            .assert_satisfied();
+
+        instances[4 * LIMBS] += Fr::one();
+        assert!(MockProver::run(K, &circuit, vec![instances])
+            .unwrap()
+            .verify()
+            .is_err());
+    }
+
+    #[test]
+    fn should_compute_instances_in_circuit_order() {
+        let params = app_params();
+        let snarks = standard_plonk_snarks::<PoseidonTranscript<_, _>>(
+            &params,
+            &[MultiOpenScheme::Shplonk; 2],
+        );
+        let instances =
+            accumulator_instances::<Bn256, LIMBS, BITS>(params.get_g()[0], &snarks).unwrap();
+        assert_eq!(instances.len(), 4 * LIMBS);
+
+        let [lhs, rhs] = [&instances[..2 * LIMBS], &instances[2 * LIMBS..]].map(|limbs| {
+            let [x, y] = [&limbs[..LIMBS], &limbs[LIMBS..]]
+                .map(|limbs| fe_from_limbs::<Fr, Fq, LIMBS, BITS>(limbs.try_into().unwrap()));
+            G1Affine::from_xy(x, y).unwrap()
+        });
+
+        let g2 = G2Prepared::from(params.g2());
+        let minus_s_g2 = G2Prepared::from(-params.s_g2());
+        assert!(bool::from(
+            Bn256::multi_miller_loop(&[(&lhs, &g2), (&rhs, &minus_s_g2)])
+                .final_exponentiation()
+                .is_identity()
+        ));
+    }
+
+    #[test]
+    fn should_reject_insufficient_limb_layout() {
+        assert!(matches!(
+            accumulator_instances::<Bn256, 3, 68>(G1Affine::generator(), &[]),
+            Err(crate::Error::InvalidLimbLayout { limbs: 3, bits: 68 })
+        ));
+        assert!(matches!(
+            accumulator_instances::<Bn256, 1, 256>(G1Affine::generator(), &[]),
+            Err(crate::Error::InvalidLimbLayout {
+                limbs: 1,
+                bits: 256
+            })
+        ));
+    }
+
+    /// Like `should_accumulate_accumulator_proof`, but with the accumulator
+    /// carried by a snark exposing it, instead of proving the aggregation
+    /// circuit, so accumulators are extracted from statements in circuit.
+    #[test]
+    fn should_accumulate_proof_carrying_accumulator() {
+        let params = app_params();
+
+        let accumulator_snark = {
+            let limbs = accumulator_instances::<Bn256, LIMBS, BITS>(
+                params.get_g()[0],
+                &[app_snark(&params), app_snark(&params)],
+            )
+            .unwrap();
+            let circuit = Passthrough::new(limbs);
+            let instances = circuit.instances();
+            create_snarks::<_, PoseidonTranscript<_, _>>(
+                &params,
+                Some(accumulator_indices::<LIMBS>()),
+                vec![(circuit, instances, MultiOpenScheme::Shplonk)],
+            )
+            .pop()
+            .unwrap()
+        };
+
+        let snarks = vec![accumulator_snark, app_snark(&params)];
+        assert!(accumulate_natively(&snarks)
+            .decide::<Bn256>(params.get_g()[0], params.g2(), params.s_g2())
+            .unwrap());
+
+        let (circuit, instances) = Accumulator::<_, LIMBS, BITS>::new(&params, snarks).unwrap();
+        MockProver::run(K, &circuit, vec![instances])
+            .unwrap()
+            .assert_satisfied();
+    }
+
+    #[test]
+    #[ignore = "proves an aggregation circuit"]
+    fn should_accumulate_accumulator_proof() {
+        let params = KZGCommitmentScheme::<Bn256>::new_params(K);
+        let mut app_params = params.clone();
+        app_params.downsize(APP_K);
+
+        let accumulator_snark = {
+            let (circuit, instances) = Accumulator::<_, LIMBS, BITS>::new(
+                &params,
+                vec![app_snark(&app_params), app_snark(&app_params)],
+            )
+            .unwrap();
+            let vk = keygen_vk::<KZGCommitmentScheme<Bn256>, _>(&params, &circuit).unwrap();
+            let pk = keygen_pk::<KZGCommitmentScheme<Bn256>, _>(&params, vk, &circuit).unwrap();
+            let protocol = compile_accumulator::<_, LIMBS>(pk.get_vk(), 1);
+            accumulate_snark::<
+                KZGCommitmentScheme<Bn256>,
+                ChallengeScalar<G1Affine>,
+                PoseidonTranscript<G1Affine, _>,
+                ProverSHPLONK<_>,
+                _,
+                _,
+            >(
+                &params,
+                &pk,
+                &[circuit],
+                &[&[instances.as_slice()]],
+                protocol,
+                OsRng,
+            )
+            .unwrap()
+        };
+
+        let snarks = vec![accumulator_snark, app_snark(&app_params)];
+        assert!(accumulate_natively(&snarks)
+            .decide::<Bn256>(params.get_g()[0], params.g2(), params.s_g2())
+            .unwrap());
+
+        let (circuit, instances) = Accumulator::<_, LIMBS, BITS>::new(&params, snarks).unwrap();
+        MockProver::run(K, &circuit, vec![instances])
+            .unwrap()
+            .assert_satisfied();
+    }
+}
@@ -11,10 +11,140 @@ pub mod circuit;
 pub mod native;
 pub mod util;
 
+#[cfg(test)]
+mod test;
+
+use halo2_wrong::halo2::plonk;
+use std::{fmt, io};
+
 #[derive(Clone, Debug)]
 pub enum Error {
-    InvalidInstances,
+    /// Number of statement columns differs from the protocol's.
+    InvalidNumStatements { expected: usize, actual: usize },
+    /// Phases of the advice columns or challenges of a circuit don't lay out,
+    /// e.g. a phase has no advice columns.
+    InvalidPhases(String),
+    /// Statement the protocol refers to, e.g. an accumulator limb, is missing.
+    MissingStatement { column: usize, row: usize },
     MissingQuery(util::Query),
     MissingChallenge(usize),
+    MissingCommitment(usize),
+    /// Proof ended before everything the protocol expects was read.
+    ProofTooShort,
+    InvalidScalarEncoding,
+    InvalidEcPointEncoding,
+    /// Accumulator limbs in the statements don't form points on the curve.
+    InvalidAccumulator,
+    /// Protocol locates an accumulator in other than `4 * LIMBS` statements.
+    InvalidAccumulatorIndices { expected: usize, actual: usize },
+    /// Accumulated point is the identity, which has no limbs to expose.
+    IdentityAccumulator,
+    /// `limbs` limbs of `bits` bits don't cover the base field, or a limb
+    /// doesn't fit into the scalar field.
+    InvalidLimbLayout { limbs: usize, bits: usize },
+    /// Accumulated proof fails the final pairing check.
+    PairingCheckFailed,
+    /// Multi-scalar multiplication has no terms to load a point with.
+    EmptyMsm,
+    /// Accumulation strategy is finalized before accumulating anything.
+    MissingAccumulator,
+    /// Chip fails to assign while loading, e.g. the sum of two points.
+    Synthesis(String),
     Transcript(std::io::ErrorKind, String),
+    /// Params, e.g. of a trusted setup, can't be read.
+    InvalidParams(String),
+    Keygen(String),
+    Proving(String),
+    ProtocolMismatch(String),
+    UnknownProtocol([u8; 32]),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidNumStatements { expected, actual } => write!(
+                f,
+                "Expected {} statement columns but got {}",
+                expected, actual
+            ),
+            Error::InvalidPhases(msg) => write!(f, "Invalid phases: {}", msg),
+            Error::MissingStatement { column, row } => {
+                write!(f, "Missing statement at row {} of column {}", row, column)
+            }
+            Error::MissingQuery(query) => write!(f, "Missing evaluation of {:?}", query),
+            Error::MissingChallenge(index) => write!(f, "Missing challenge {}", index),
+            Error::MissingCommitment(poly) => write!(f, "Missing commitment of poly {}", poly),
+            Error::ProofTooShort => write!(f, "Proof is too short"),
+            Error::InvalidScalarEncoding => write!(f, "Invalid scalar encoding in proof"),
+            Error::InvalidEcPointEncoding => {
+                write!(f, "Invalid elliptic curve point encoding in proof")
+            }
+            Error::InvalidAccumulator => write!(f, "Accumulator limbs are not on the curve"),
+            Error::InvalidAccumulatorIndices { expected, actual } => write!(
+                f,
+                "Expected accumulator in {} statements but got {}",
+                expected, actual
+            ),
+            Error::IdentityAccumulator => write!(f, "Accumulator is the identity"),
+            Error::InvalidLimbLayout { limbs, bits } => {
+                write!(f, "Invalid limb layout of {} limbs of {} bits", limbs, bits)
+            }
+            Error::PairingCheckFailed => write!(f, "Pairing check failed"),
+            Error::EmptyMsm => write!(f, "Multi-scalar multiplication has no terms"),
+            Error::MissingAccumulator => write!(f, "Nothing has been accumulated"),
+            Error::Synthesis(msg) => write!(f, "Synthesis failed: {}", msg),
+            Error::Transcript(kind, msg) => write!(f, "Transcript error ({:?}): {}", kind, msg),
+            Error::InvalidParams(msg) => write!(f, "Invalid params: {}", msg),
+            Error::Keygen(msg) => write!(f, "Key generation failed: {}", msg),
+            Error::Proving(msg) => write!(f, "Proving failed: {}", msg),
+            Error::ProtocolMismatch(msg) => write!(f, "Protocol mismatch: {}", msg),
+            Error::UnknownProtocol(digest) => {
+                write!(f, "Unknown protocol 0x{}", hex::encode(digest))
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// An [`Error`] wrapped by `From<Error> for io::Error` is unwrapped again.
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        let (kind, msg) = (err.kind(), err.to_string());
+        if kind == io::ErrorKind::UnexpectedEof {
+            return Error::ProofTooShort;
+        }
+        match err.into_inner().map(|inner| inner.downcast::<Error>()) {
+            Some(Ok(err)) => *err,
+            _ => Error::Transcript(kind, msg),
+        }
+    }
+}
+
+impl From<plonk::Error> for Error {
+    fn from(err: plonk::Error) -> Self {
+        match err {
+            plonk::Error::Transcript(err) => err.into(),
+            err => Error::Synthesis(format!("{:?}", err)),
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::ProofTooShort => io::ErrorKind::UnexpectedEof.into(),
+            Error::Transcript(kind, msg) => io::Error::new(kind, msg),
+            err => io::Error::new(io::ErrorKind::InvalidData, err),
+        }
+    }
+}
+
+/// Every error keeps its cause by being wrapped in `Error::Transcript`, the
+/// only variant of halo2's error carrying one, and comes back unchanged
+/// through `From<plonk::Error> for Error`.
+impl From<Error> for plonk::Error {
+    fn from(err: Error) -> Self {
+        plonk::Error::Transcript(err.into())
+    }
 }
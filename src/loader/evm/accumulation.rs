@@ -0,0 +1,314 @@
+use crate::{
+    loader::{
+        evm::loader::{EcPoint, EvmLoader, Scalar},
+        LoadedEcPoint,
+    },
+    protocol::Protocol,
+    scheme::{AccumulationStrategy, Accumulator, SameCurveAccumulation, MSM},
+    util::{PrimeCurveAffine, Transcript},
+    Error,
+};
+use halo2_wrong::curves::bn256::{G1Affine, G2Affine, G1};
+use std::rc::Rc;
+
+impl<const LIMBS: usize, const BITS: usize> SameCurveAccumulation<G1, Rc<EvmLoader>, LIMBS, BITS> {
+    pub fn finalize(self, g1: G1Affine) -> Result<(EcPoint, EcPoint), Error> {
+        self.accumulator
+            .ok_or(Error::MissingAccumulator)?
+            .evaluate(g1.to_curve())
+    }
+
+    /// Emits the pairing check of the accumulator, reverting if it fails.
+    pub fn decide(self, g1: G1Affine, g2: G2Affine, s_g2: G2Affine) -> Result<(), Error> {
+        let (lhs, rhs) = self.finalize(g1)?;
+        lhs.loader().pairing(&lhs, g2, &rhs, -s_g2);
+        Ok(())
+    }
+}
+
+impl<T, P, const LIMBS: usize, const BITS: usize> AccumulationStrategy<G1, Rc<EvmLoader>, T, P>
+    for SameCurveAccumulation<G1, Rc<EvmLoader>, LIMBS, BITS>
+where
+    T: Transcript<G1, Rc<EvmLoader>>,
+{
+    type Output = ();
+
+    fn extract_accumulator(
+        &self,
+        protocol: &Protocol<G1>,
+        loader: &Rc<EvmLoader>,
+        transcript: &mut T,
+        statements: &[Vec<Scalar>],
+    ) -> Result<Option<Accumulator<G1, Rc<EvmLoader>>>, Error> {
+        let accumulator_indices = match protocol.accumulator_indices.as_ref() {
+            Some(accumulator_indices) => accumulator_indices,
+            None => return Ok(None),
+        };
+
+        let challenges = transcript.squeeze_n_challenges(accumulator_indices.len());
+        let accumulators = accumulator_indices
+            .iter()
+            .map(|indices| {
+                if indices.len() != 4 * LIMBS {
+                    return Err(Error::InvalidAccumulatorIndices {
+                        expected: 4 * LIMBS,
+                        actual: indices.len(),
+                    });
+                }
+                let limbs = indices
+                    .iter()
+                    .map(|&(column, row)| {
+                        statements
+                            .get(column)
+                            .and_then(|statements| statements.get(row))
+                            .cloned()
+                            .ok_or(Error::MissingStatement { column, row })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let [lhs, rhs] = [&limbs[..2 * LIMBS], &limbs[2 * LIMBS..]].map(|limbs| {
+                    loader.ec_point_from_limbs::<LIMBS, BITS>(
+                        limbs[..LIMBS].try_into().unwrap(),
+                        limbs[LIMBS..].try_into().unwrap(),
+                    )
+                });
+                Ok(Accumulator::new(MSM::base(lhs), MSM::base(rhs)))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Some(Accumulator::random_linear_combine(
+            challenges.into_iter().map(Some).zip(accumulators),
+        )))
+    }
+
+    fn process(
+        &mut self,
+        _: &Rc<EvmLoader>,
+        transcript: &mut T,
+        _: P,
+        accumulator: Accumulator<G1, Rc<EvmLoader>>,
+    ) -> Result<Self::Output, Error> {
+        match self.accumulator.take() {
+            Some(curr_accumulator) => {
+                self.accumulator = Some(Accumulator::random_linear_combine([
+                    (None, accumulator),
+                    (Some(transcript.squeeze_challenge()), curr_accumulator),
+                ]));
+            }
+            None => self.accumulator = Some(accumulator),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        circuit::{
+            accumulator_indices, accumulator_instances, compile_accumulator, Accumulator, BITS,
+            LIMBS,
+        },
+        loader::{
+            evm::{encode_calldata, gen_verifier_code},
+            native::{ChallengeScalar, Keccak256Transcript},
+        },
+        native::Snark,
+        scheme::MultiOpenScheme,
+        test::{app_params, app_snark, create_snarks, standard_plonk_snark, Passthrough, APP_K},
+        util::{accumulate_snark, Field},
+    };
+    use halo2_wrong::curves::bn256::{Bn256, Fr, G1Affine, G1};
+    use halo2_wrong::halo2::{
+        plonk::{keygen_pk, keygen_vk},
+        poly::{
+            commitment::{CommitmentScheme, Params},
+            kzg::{
+                commitment::{KZGCommitmentScheme, ParamsKZG},
+                multiopen::ProverGWC,
+            },
+        },
+    };
+    use rand::rngs::OsRng;
+    use revm::{AccountInfo, Bytecode, InMemoryDB, Return, TransactTo, B160, EVM};
+
+    const K: u32 = 21;
+
+    /// Runs `code` as the code of a called contract, returning whether it
+    /// succeeded and the gas used.
+    fn execute(code: Vec<u8>, calldata: Vec<u8>) -> (bool, u64) {
+        let verifier = B160::from_low_u64_be(0xff);
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            verifier,
+            AccountInfo {
+                code: Some(Bytecode::new_raw(code.into())),
+                ..Default::default()
+            },
+        );
+
+        let mut evm = EVM::new();
+        evm.database(db);
+        evm.env.tx.caller = B160::from_low_u64_be(0xfe);
+        evm.env.tx.transact_to = TransactTo::Call(verifier);
+        evm.env.tx.data = calldata.into();
+        evm.env.tx.gas_limit = u64::MAX;
+
+        let (result, _) = evm.transact();
+        (
+            matches!(result.exit_reason, Return::Stop | Return::Return),
+            result.gas_used,
+        )
+    }
+
+    fn verifier_code(params: &ParamsKZG<Bn256>, snark: &Snark<G1>) -> Vec<u8> {
+        gen_verifier_code::<LIMBS, BITS>(
+            snark.protocol(),
+            snark.scheme(),
+            &snark.statements().iter().map(Vec::len).collect::<Vec<_>>(),
+            params.get_g()[0],
+            params.g2(),
+            params.s_g2(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn should_accept_valid_proof_on_evm() {
+        let params = app_params();
+        for scheme in [MultiOpenScheme::Plonk, MultiOpenScheme::Shplonk] {
+            let snark = standard_plonk_snark::<Keccak256Transcript<_, _>>(&params, scheme);
+            let code = verifier_code(&params, &snark);
+            let (accepted, _) = execute(code, encode_calldata(snark.statements(), snark.proof()));
+            assert!(accepted);
+        }
+    }
+
+    #[test]
+    fn should_reject_invalid_calldata_on_evm() {
+        let params = app_params();
+        let snark =
+            standard_plonk_snark::<Keccak256Transcript<_, _>>(&params, MultiOpenScheme::Shplonk);
+        let code = verifier_code(&params, &snark);
+
+        let mut statements = snark.statements().to_vec();
+        statements[0][0] += Fr::one();
+        let (accepted, _) = execute(code.clone(), encode_calldata(&statements, snark.proof()));
+        assert!(!accepted);
+
+        let mut proof = snark.proof().to_vec();
+        let last = proof.len() - 1;
+        proof[last] ^= 1;
+        let (accepted, _) = execute(code.clone(), encode_calldata(snark.statements(), &proof));
+        assert!(!accepted);
+
+        let calldata = encode_calldata(snark.statements(), snark.proof());
+        let (accepted, _) = execute(code, calldata[..calldata.len() - 1].to_vec());
+        assert!(!accepted);
+    }
+
+    /// Verifies a snark carrying the accumulator of two others in its
+    /// statements, as a proof of [`Accumulator`] does, without proving the
+    /// aggregation circuit itself, so limbs are decoded into points on the EVM.
+    #[test]
+    fn should_accept_proof_carrying_accumulator_on_evm() {
+        let params = app_params();
+        let app_snarks = [app_snark(&params), app_snark(&params)];
+        let limbs =
+            accumulator_instances::<Bn256, LIMBS, BITS>(params.get_g()[0], &app_snarks).unwrap();
+
+        let circuit = Passthrough::new(limbs);
+        let instances = circuit.instances();
+        let snark = create_snarks::<_, Keccak256Transcript<_, _>>(
+            &params,
+            Some(accumulator_indices::<LIMBS>()),
+            vec![(circuit, instances, MultiOpenScheme::Plonk)],
+        )
+        .pop()
+        .unwrap();
+
+        let code = verifier_code(&params, &snark);
+        let (accepted, _) = execute(code, encode_calldata(snark.statements(), snark.proof()));
+        assert!(accepted);
+    }
+
+    /// Verifies snarks carrying a tampered accumulator in their statements,
+    /// each proven anew so only the accumulator fails, once off the curve by
+    /// a tampered limb and once failing the pairing check with `lhs` and `rhs`
+    /// swapped.
+    #[test]
+    fn should_reject_proof_carrying_invalid_accumulator_on_evm() {
+        let params = app_params();
+        let app_snarks = [app_snark(&params), app_snark(&params)];
+        let limbs =
+            accumulator_instances::<Bn256, LIMBS, BITS>(params.get_g()[0], &app_snarks).unwrap();
+
+        let mut tampered = limbs.clone();
+        tampered[0] += Fr::one();
+        let mut swapped = limbs;
+        swapped.rotate_left(2 * LIMBS);
+
+        for limbs in [tampered, swapped] {
+            let circuit = Passthrough::new(limbs);
+            let instances = circuit.instances();
+            let snark = create_snarks::<_, Keccak256Transcript<_, _>>(
+                &params,
+                Some(accumulator_indices::<LIMBS>()),
+                vec![(circuit, instances, MultiOpenScheme::Plonk)],
+            )
+            .pop()
+            .unwrap();
+
+            let code = verifier_code(&params, &snark);
+            let (accepted, _) = execute(code, encode_calldata(snark.statements(), snark.proof()));
+            assert!(!accepted);
+        }
+    }
+
+    /// Proves [`Accumulator`] of two snarks with GWC and Keccak256, so the
+    /// verifier decodes accumulator limbs the circuit constrains, and checks
+    /// a tampered limb reverts. Both share the proof, as proving takes long.
+    #[test]
+    #[ignore = "proves an aggregation circuit"]
+    fn should_accept_aggregation_proof_on_evm() {
+        let params = KZGCommitmentScheme::<Bn256>::new_params(K);
+        let mut app_params = params.clone();
+        app_params.downsize(APP_K);
+
+        let (circuit, instances) = Accumulator::<_, LIMBS, BITS>::new(
+            &params,
+            vec![app_snark(&app_params), app_snark(&app_params)],
+        )
+        .unwrap();
+        let vk = keygen_vk::<KZGCommitmentScheme<Bn256>, _>(&params, &circuit).unwrap();
+        let pk = keygen_pk::<KZGCommitmentScheme<Bn256>, _>(&params, vk, &circuit).unwrap();
+        let protocol = compile_accumulator::<_, LIMBS>(pk.get_vk(), 1);
+        let snark = accumulate_snark::<
+            KZGCommitmentScheme<Bn256>,
+            ChallengeScalar<G1Affine>,
+            Keccak256Transcript<G1Affine, _>,
+            ProverGWC<_>,
+            _,
+            _,
+        >(
+            &params,
+            &pk,
+            &[circuit],
+            &[&[instances.as_slice()]],
+            protocol,
+            OsRng,
+        )
+        .unwrap();
+
+        let code = verifier_code(&params, &snark);
+        let (accepted, _) = execute(
+            code.clone(),
+            encode_calldata(snark.statements(), snark.proof()),
+        );
+        assert!(accepted);
+
+        // First limb of the x coordinate of the accumulated lhs
+        let mut statements = snark.statements().to_vec();
+        statements[0][0] += Fr::one();
+        let (accepted, _) = execute(code, encode_calldata(&statements, snark.proof()));
+        assert!(!accepted);
+    }
+}
@@ -0,0 +1,127 @@
+use num_bigint::BigUint;
+
+/// Offset of the `JUMPDEST` every failed check jumps to, right after the
+/// `PUSH1 0x08 JUMP` that skips over the revert block at the start of code.
+const REVERT: u8 = 0x03;
+
+macro_rules! opcodes {
+    ($($name:ident = $byte:expr,)*) => {
+        $(
+            pub fn $name(&mut self) -> &mut Self {
+                self.bytes.push($byte);
+                self
+            }
+        )*
+    };
+}
+
+/// Builder of straight-line EVM bytecode.
+///
+/// Besides plain pushes it supports pushes of memory offsets relative to a
+/// deferred base, for regions whose position is only known once everything
+/// else has been allocated, which get patched by [`Code::finalize`].
+#[derive(Clone, Debug)]
+pub struct Code {
+    bytes: Vec<u8>,
+    deferred: Vec<(usize, usize)>,
+}
+
+impl Code {
+    pub fn new() -> Self {
+        let mut code = Self {
+            bytes: Vec::new(),
+            deferred: Vec::new(),
+        };
+        // PUSH1 0x08 JUMP JUMPDEST PUSH1 0x00 DUP1 REVERT JUMPDEST
+        code.bytes
+            .extend([0x60, 0x08, 0x56, 0x5b, 0x60, 0x00, 0x80, 0xfd, 0x5b]);
+        code
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn push(&mut self, value: impl Into<BigUint>) -> &mut Self {
+        let bytes = value.into().to_bytes_be();
+        assert!(bytes.len() <= 32);
+        self.bytes.push(0x5f + bytes.len() as u8);
+        self.bytes.extend(bytes);
+        self
+    }
+
+    /// Pushes `offset` relative to the base given to [`Code::finalize`].
+    pub fn push_deferred(&mut self, offset: usize) -> &mut Self {
+        // PUSH3, which is enough for any memory offset affordable in gas
+        self.bytes.push(0x62);
+        self.deferred.push((self.bytes.len(), offset));
+        self.bytes.extend([0; 3]);
+        self
+    }
+
+    /// Jumps to the revert block if the top of stack is zero, consuming it.
+    pub fn revert_if_zero(&mut self) -> &mut Self {
+        self.iszero();
+        self.bytes.extend([0x60, REVERT, 0x57]);
+        self
+    }
+
+    /// Terminates the code and patches deferred pushes with `base`.
+    pub fn finalize(mut self, base: usize) -> Vec<u8> {
+        self.stop();
+        for (pos, offset) in self.deferred {
+            let value = base + offset;
+            assert!(value < 1 << 24);
+            self.bytes[pos..pos + 3].copy_from_slice(&(value as u32).to_be_bytes()[1..]);
+        }
+        self.bytes
+    }
+
+    opcodes!(
+        stop = 0x00,
+        add = 0x01,
+        sub = 0x03,
+        r#mod = 0x06,
+        addmod = 0x08,
+        mulmod = 0x09,
+        lt = 0x10,
+        eq = 0x14,
+        iszero = 0x15,
+        shl = 0x1b,
+        keccak256 = 0x20,
+        calldataload = 0x35,
+        calldatasize = 0x36,
+        pop = 0x50,
+        mload = 0x51,
+        mstore = 0x52,
+        mstore8 = 0x53,
+        gas = 0x5a,
+        dup1 = 0x80,
+        dup2 = 0x81,
+        swap1 = 0x90,
+        staticcall = 0xfa,
+    );
+}
+
+impl Default for Code {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Wraps `runtime` into creation code that returns it on deployment.
+pub fn deployment_code(runtime: &[u8]) -> Vec<u8> {
+    assert!(runtime.len() < 1 << 16);
+    let len = (runtime.len() as u16).to_be_bytes();
+    // PUSH2 len DUP1 PUSH1 0x0c PUSH1 0x00 CODECOPY PUSH1 0x00 RETURN
+    [
+        0x61, len[0], len[1], 0x80, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, 0x00, 0xf3,
+    ]
+    .into_iter()
+    .chain(runtime.iter().copied())
+    .collect()
+}
@@ -0,0 +1,609 @@
+use crate::{
+    loader::{
+        evm::code::{deployment_code, Code},
+        EcPointLoader, LoadedEcPoint, LoadedScalar, Loader, ScalarLoader,
+    },
+    util::{fe_to_big, modulus, Curve, Field, FieldOps},
+    Error,
+};
+use halo2_wrong::curves::{
+    bn256::{Fq, Fr, G2Affine, G1},
+    CurveAffine,
+};
+use num_bigint::BigUint;
+use std::{
+    cell::{RefCell, RefMut},
+    fmt::{self, Debug},
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    rc::Rc,
+};
+
+const EC_ADD: u8 = 0x06;
+const EC_MUL: u8 = 0x07;
+const EC_PAIRING: u8 = 0x08;
+const MOD_EXP: u8 = 0x05;
+
+#[derive(Clone, Debug)]
+pub enum Value<T> {
+    Constant(T),
+    Memory(usize),
+}
+
+/// Loader emitting EVM bytecode that performs the operations instead of
+/// computing them.
+///
+/// Every loaded value lives at a static memory offset and every check, like
+/// a precompile call failing or a scalar read from calldata not being in the
+/// field, jumps to a revert. Code that reaches the end stops successfully, so
+/// [`EvmLoader::runtime_code`] run with valid calldata accepts it.
+#[derive(Debug)]
+pub struct EvmLoader {
+    code: RefCell<Code>,
+    ptr: RefCell<usize>,
+    scalar_modulus: BigUint,
+    base_modulus: BigUint,
+}
+
+impl EvmLoader {
+    pub fn new() -> Rc<Self> {
+        Rc::new(Self {
+            code: RefCell::new(Code::new()),
+            ptr: RefCell::new(0),
+            scalar_modulus: modulus::<Fr>(),
+            base_modulus: modulus::<Fq>(),
+        })
+    }
+
+    /// Returns the code emitted so far, with regions allocated by
+    /// [`Code::push_deferred`] placed after all other memory.
+    pub fn runtime_code(&self) -> Vec<u8> {
+        self.code.borrow().clone().finalize(*self.ptr.borrow())
+    }
+
+    pub fn deployment_code(&self) -> Vec<u8> {
+        deployment_code(&self.runtime_code())
+    }
+
+    pub(super) fn code_mut(&self) -> RefMut<'_, Code> {
+        self.code.borrow_mut()
+    }
+
+    pub(super) fn scalar_modulus(&self) -> &BigUint {
+        &self.scalar_modulus
+    }
+
+    fn allocate(&self, size: usize) -> usize {
+        let ptr = *self.ptr.borrow();
+        *self.ptr.borrow_mut() += size;
+        ptr
+    }
+
+    pub(super) fn scalar(self: &Rc<Self>, value: Value<Fr>) -> Scalar {
+        Scalar {
+            loader: self.clone(),
+            value,
+        }
+    }
+
+    fn ec_point(self: &Rc<Self>, ptr: usize) -> EcPoint {
+        EcPoint {
+            loader: self.clone(),
+            ptr,
+        }
+    }
+
+    /// Stores the top of stack as a new scalar.
+    pub(super) fn scalar_from_stack(self: &Rc<Self>) -> Scalar {
+        let ptr = self.allocate(0x20);
+        self.code_mut().push(ptr).mstore();
+        self.scalar(Value::Memory(ptr))
+    }
+
+    /// Pushes `scalar` onto the stack.
+    pub(super) fn push_scalar(&self, scalar: &Scalar) {
+        match scalar.value {
+            Value::Constant(constant) => {
+                self.code_mut().push(fe_to_big(constant));
+            }
+            Value::Memory(ptr) => {
+                self.code_mut().push(ptr).mload();
+            }
+        }
+    }
+
+    /// Loads the 32 bytes big-endian scalar at `offset` of calldata, reverting
+    /// if it's not in the field.
+    pub fn calldata_load_scalar(self: &Rc<Self>, offset: usize) -> Scalar {
+        self.code_mut()
+            .push(offset)
+            .calldataload()
+            .dup1()
+            .push(self.scalar_modulus.clone())
+            .swap1()
+            .lt()
+            .revert_if_zero();
+        self.scalar_from_stack()
+    }
+
+    /// Loads the point encoded as big-endian `x` and `y` at `offset` of
+    /// calldata. It's checked to be on curve by the precompiles using it.
+    pub fn calldata_load_ec_point(self: &Rc<Self>, offset: usize) -> EcPoint {
+        let ptr = self.allocate(0x40);
+        self.code_mut()
+            .push(offset)
+            .calldataload()
+            .push(ptr)
+            .mstore()
+            .push(offset + 0x20)
+            .calldataload()
+            .push(ptr + 0x20)
+            .mstore();
+        self.ec_point(ptr)
+    }
+
+    /// Composes a point from limbs of `BITS` bits of its coordinates, in the
+    /// layout of [`crate::util::fe_to_limbs`].
+    pub fn ec_point_from_limbs<const LIMBS: usize, const BITS: usize>(
+        self: &Rc<Self>,
+        x_limbs: &[Scalar; LIMBS],
+        y_limbs: &[Scalar; LIMBS],
+    ) -> EcPoint {
+        let ptr = self.allocate(0x40);
+        for (limbs, ptr) in [(x_limbs, ptr), (y_limbs, ptr + 0x20)] {
+            for (idx, limb) in limbs.iter().enumerate() {
+                self.push_scalar(limb);
+                if idx > 0 {
+                    self.code_mut().push(idx * BITS).shl().add();
+                }
+            }
+            self.code_mut().push(ptr).mstore();
+        }
+        self.ec_point(ptr)
+    }
+
+    /// Reverts unless calldata is exactly `size` bytes long.
+    pub fn assert_calldata_size(&self, size: usize) {
+        self.code_mut()
+            .calldatasize()
+            .push(size)
+            .eq()
+            .revert_if_zero();
+    }
+
+    /// Reverts unless `e(lhs, g2) * e(rhs, minus_s_g2) == 1`.
+    pub fn pairing(
+        self: &Rc<Self>,
+        lhs: &EcPoint,
+        g2: G2Affine,
+        rhs: &EcPoint,
+        minus_s_g2: G2Affine,
+    ) {
+        let ptr = self.allocate(0x180);
+        for (idx, (ec_point, g2)) in [(lhs, g2), (rhs, minus_s_g2)].into_iter().enumerate() {
+            let ptr = ptr + idx * 0xc0;
+            self.copy(ec_point.ptr, ptr);
+            self.copy(ec_point.ptr + 0x20, ptr + 0x20);
+            let coordinates = g2.coordinates().unwrap();
+            for (idx, value) in [
+                coordinates.x().c1,
+                coordinates.x().c0,
+                coordinates.y().c1,
+                coordinates.y().c0,
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                self.code_mut()
+                    .push(fe_to_big(value))
+                    .push(ptr + 0x40 + idx * 0x20)
+                    .mstore();
+            }
+        }
+        self.staticcall(EC_PAIRING, ptr, 0x180, ptr, 0x20);
+        self.code_mut().push(ptr).mload().revert_if_zero();
+    }
+
+    fn copy(&self, src: usize, dst: usize) {
+        self.code_mut().push(src).mload().push(dst).mstore();
+    }
+
+    fn staticcall(
+        &self,
+        precompile: u8,
+        input: usize,
+        input_len: usize,
+        output: usize,
+        output_len: usize,
+    ) {
+        self.code_mut()
+            .push(output_len)
+            .push(output)
+            .push(input_len)
+            .push(input)
+            .push(precompile)
+            .gas()
+            .staticcall()
+            .revert_if_zero();
+    }
+
+    fn ec_point_const(self: &Rc<Self>, value: &G1) -> EcPoint {
+        let ptr = self.allocate(0x40);
+        let coordinates = value.to_affine().coordinates();
+        let [x, y] = Option::<[_; 2]>::from(
+            coordinates.map(|coordinates| [*coordinates.x(), *coordinates.y()]),
+        )
+        .unwrap_or([Fq::zero(); 2]);
+        self.code_mut()
+            .push(fe_to_big(x))
+            .push(ptr)
+            .mstore()
+            .push(fe_to_big(y))
+            .push(ptr + 0x20)
+            .mstore();
+        self.ec_point(ptr)
+    }
+
+    fn ec_point_add(self: &Rc<Self>, lhs: &EcPoint, rhs: &EcPoint) -> EcPoint {
+        let ptr = self.allocate(0x80);
+        self.copy(lhs.ptr, ptr);
+        self.copy(lhs.ptr + 0x20, ptr + 0x20);
+        self.copy(rhs.ptr, ptr + 0x40);
+        self.copy(rhs.ptr + 0x20, ptr + 0x60);
+        self.staticcall(EC_ADD, ptr, 0x80, ptr, 0x40);
+        self.ec_point(ptr)
+    }
+
+    fn ec_point_mul(self: &Rc<Self>, ec_point: &EcPoint, scalar: &Scalar) -> EcPoint {
+        let ptr = self.allocate(0x60);
+        self.copy(ec_point.ptr, ptr);
+        self.copy(ec_point.ptr + 0x20, ptr + 0x20);
+        self.push_scalar(scalar);
+        self.code_mut().push(ptr + 0x40).mstore();
+        self.staticcall(EC_MUL, ptr, 0x60, ptr, 0x40);
+        self.ec_point(ptr)
+    }
+
+    fn ec_point_neg(self: &Rc<Self>, ec_point: &EcPoint) -> EcPoint {
+        let ptr = self.allocate(0x40);
+        self.copy(ec_point.ptr, ptr);
+        // (p - y) % p, which keeps the identity (0, 0) as is
+        self.code_mut()
+            .push(self.base_modulus.clone())
+            .push(ec_point.ptr + 0x20)
+            .mload()
+            .push(self.base_modulus.clone())
+            .sub()
+            .r#mod()
+            .push(ptr + 0x20)
+            .mstore();
+        self.ec_point(ptr)
+    }
+
+    fn add(self: &Rc<Self>, lhs: &Scalar, rhs: &Scalar) -> Scalar {
+        if let (Value::Constant(lhs), Value::Constant(rhs)) = (&lhs.value, &rhs.value) {
+            return self.scalar(Value::Constant(*lhs + rhs));
+        }
+        self.code_mut().push(self.scalar_modulus.clone());
+        self.push_scalar(rhs);
+        self.push_scalar(lhs);
+        self.code_mut().addmod();
+        self.scalar_from_stack()
+    }
+
+    fn sub(self: &Rc<Self>, lhs: &Scalar, rhs: &Scalar) -> Scalar {
+        if let (Value::Constant(lhs), Value::Constant(rhs)) = (&lhs.value, &rhs.value) {
+            return self.scalar(Value::Constant(*lhs - rhs));
+        }
+        self.code_mut().push(self.scalar_modulus.clone());
+        self.push_scalar(rhs);
+        self.code_mut().push(self.scalar_modulus.clone()).sub();
+        self.push_scalar(lhs);
+        self.code_mut().addmod();
+        self.scalar_from_stack()
+    }
+
+    fn mul(self: &Rc<Self>, lhs: &Scalar, rhs: &Scalar) -> Scalar {
+        if let (Value::Constant(lhs), Value::Constant(rhs)) = (&lhs.value, &rhs.value) {
+            return self.scalar(Value::Constant(*lhs * rhs));
+        }
+        self.code_mut().push(self.scalar_modulus.clone());
+        self.push_scalar(rhs);
+        self.push_scalar(lhs);
+        self.code_mut().mulmod();
+        self.scalar_from_stack()
+    }
+
+    fn neg(self: &Rc<Self>, scalar: &Scalar) -> Scalar {
+        if let Value::Constant(constant) = scalar.value {
+            return self.scalar(Value::Constant(-constant));
+        }
+        self.code_mut().push(self.scalar_modulus.clone());
+        self.push_scalar(scalar);
+        self.code_mut()
+            .push(self.scalar_modulus.clone())
+            .sub()
+            .r#mod();
+        self.scalar_from_stack()
+    }
+
+    /// Inverts by exponentiation to `r - 2` with the modexp precompile, which
+    /// maps zero to zero.
+    fn invert(self: &Rc<Self>, scalar: &Scalar) -> Scalar {
+        if let Value::Constant(constant) = scalar.value {
+            return self.scalar(Value::Constant(
+                Option::from(Field::invert(&constant)).unwrap_or_else(Fr::zero),
+            ));
+        }
+        let ptr = self.allocate(0xc0);
+        for idx in 0..3 {
+            self.code_mut().push(0x20u8).push(ptr + idx * 0x20).mstore();
+        }
+        self.push_scalar(scalar);
+        self.code_mut()
+            .push(ptr + 0x60)
+            .mstore()
+            .push(&self.scalar_modulus - 2u8)
+            .push(ptr + 0x80)
+            .mstore()
+            .push(self.scalar_modulus.clone())
+            .push(ptr + 0xa0)
+            .mstore();
+        self.staticcall(MOD_EXP, ptr, 0xc0, ptr, 0x20);
+        self.scalar(Value::Memory(ptr))
+    }
+}
+
+#[derive(Clone)]
+pub struct Scalar {
+    loader: Rc<EvmLoader>,
+    value: Value<Fr>,
+}
+
+impl Scalar {
+    pub fn value(&self) -> &Value<Fr> {
+        &self.value
+    }
+}
+
+impl LoadedScalar<Fr> for Scalar {
+    type Loader = Rc<EvmLoader>;
+
+    fn loader(&self) -> &Self::Loader {
+        &self.loader
+    }
+}
+
+impl Debug for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scalar")
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl FieldOps for Scalar {
+    fn invert(&self) -> Option<Self> {
+        Some(self.loader.invert(self))
+    }
+}
+
+impl Add for Scalar {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.loader.add(&self, &rhs)
+    }
+}
+
+impl Sub for Scalar {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.loader.sub(&self, &rhs)
+    }
+}
+
+impl Mul for Scalar {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.loader.mul(&self, &rhs)
+    }
+}
+
+impl Neg for Scalar {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.loader.neg(&self)
+    }
+}
+
+impl<'a> Add<&'a Self> for Scalar {
+    type Output = Self;
+
+    fn add(self, rhs: &'a Self) -> Self::Output {
+        self.loader.add(&self, rhs)
+    }
+}
+
+impl<'a> Sub<&'a Self> for Scalar {
+    type Output = Self;
+
+    fn sub(self, rhs: &'a Self) -> Self::Output {
+        self.loader.sub(&self, rhs)
+    }
+}
+
+impl<'a> Mul<&'a Self> for Scalar {
+    type Output = Self;
+
+    fn mul(self, rhs: &'a Self) -> Self::Output {
+        self.loader.mul(&self, rhs)
+    }
+}
+
+impl AddAssign for Scalar {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = self.loader.add(self, &rhs)
+    }
+}
+
+impl SubAssign for Scalar {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = self.loader.sub(self, &rhs)
+    }
+}
+
+impl MulAssign for Scalar {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = self.loader.mul(self, &rhs)
+    }
+}
+
+impl<'a> AddAssign<&'a Self> for Scalar {
+    fn add_assign(&mut self, rhs: &'a Self) {
+        *self = self.loader.add(self, rhs)
+    }
+}
+
+impl<'a> SubAssign<&'a Self> for Scalar {
+    fn sub_assign(&mut self, rhs: &'a Self) {
+        *self = self.loader.sub(self, rhs)
+    }
+}
+
+impl<'a> MulAssign<&'a Self> for Scalar {
+    fn mul_assign(&mut self, rhs: &'a Self) {
+        *self = self.loader.mul(self, rhs)
+    }
+}
+
+/// Point stored in memory as big-endian `x` followed by `y`, with the
+/// identity as `(0, 0)`, which is the encoding the precompiles take.
+#[derive(Clone)]
+pub struct EcPoint {
+    loader: Rc<EvmLoader>,
+    ptr: usize,
+}
+
+impl EcPoint {
+    pub fn ptr(&self) -> usize {
+        self.ptr
+    }
+}
+
+impl PartialEq for EcPoint {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr
+    }
+}
+
+impl Debug for EcPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EcPoint").field("ptr", &self.ptr).finish()
+    }
+}
+
+impl LoadedEcPoint<G1> for EcPoint {
+    type Loader = Rc<EvmLoader>;
+
+    fn loader(&self) -> &Self::Loader {
+        &self.loader
+    }
+
+    fn multi_scalar_multiplication(
+        pairs: impl IntoIterator<Item = (Scalar, EcPoint)>,
+    ) -> Result<Self, Error> {
+        pairs
+            .into_iter()
+            .map(|(scalar, ec_point)| match scalar.value {
+                Value::Constant(constant) if constant == Fr::one() => ec_point,
+                _ => ec_point.loader.ec_point_mul(&ec_point, &scalar),
+            })
+            .reduce(|acc, ec_point| acc.loader.ec_point_add(&acc, &ec_point))
+            .ok_or(Error::EmptyMsm)
+    }
+}
+
+impl Add for EcPoint {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.loader.ec_point_add(&self, &rhs)
+    }
+}
+
+impl Sub for EcPoint {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.loader.ec_point_add(&self, &-rhs)
+    }
+}
+
+impl Neg for EcPoint {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.loader.ec_point_neg(&self)
+    }
+}
+
+impl<'a> Add<&'a Self> for EcPoint {
+    type Output = Self;
+
+    fn add(self, rhs: &'a Self) -> Self::Output {
+        self.loader.ec_point_add(&self, rhs)
+    }
+}
+
+impl<'a> Sub<&'a Self> for EcPoint {
+    type Output = Self;
+
+    fn sub(self, rhs: &'a Self) -> Self::Output {
+        self.loader.ec_point_add(&self, &-rhs.clone())
+    }
+}
+
+impl AddAssign for EcPoint {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = self.loader.ec_point_add(self, &rhs)
+    }
+}
+
+impl SubAssign for EcPoint {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = self.loader.ec_point_add(self, &-rhs)
+    }
+}
+
+impl<'a> AddAssign<&'a Self> for EcPoint {
+    fn add_assign(&mut self, rhs: &'a Self) {
+        *self = self.loader.ec_point_add(self, rhs)
+    }
+}
+
+impl<'a> SubAssign<&'a Self> for EcPoint {
+    fn sub_assign(&mut self, rhs: &'a Self) {
+        *self = self.loader.ec_point_add(self, &-rhs.clone())
+    }
+}
+
+impl EcPointLoader<G1> for Rc<EvmLoader> {
+    type LoadedEcPoint = EcPoint;
+
+    fn ec_point_load_const(&self, value: &G1) -> EcPoint {
+        self.ec_point_const(value)
+    }
+}
+
+impl ScalarLoader<Fr> for Rc<EvmLoader> {
+    type LoadedScalar = Scalar;
+
+    fn load_const(&self, value: &Fr) -> Scalar {
+        self.scalar(Value::Constant(*value))
+    }
+}
+
+impl Loader<G1> for Rc<EvmLoader> {}
@@ -0,0 +1,78 @@
+mod accumulation;
+mod code;
+mod loader;
+mod transcript;
+
+pub use code::deployment_code;
+pub use loader::{EcPoint, EvmLoader, Scalar, Value};
+pub use transcript::EvmTranscript;
+
+use crate::{
+    protocol::Protocol,
+    scheme::{MultiOpenScheme, SameCurveAccumulation},
+    util::fe_to_big,
+    Error,
+};
+use halo2_wrong::curves::bn256::{Fr, G1Affine, G2Affine, G1};
+
+/// Generates runtime code verifying proofs of `protocol` created with
+/// `scheme` and [`crate::loader::native::Keccak256Transcript`], taking
+/// calldata encoded by [`encode_calldata`] with `num_statements[i]`
+/// statements in column `i`.
+///
+/// The code stops successfully if the accumulator passes the pairing check
+/// with `g2` and `s_g2`, and reverts otherwise.
+pub fn gen_verifier_code<const LIMBS: usize, const BITS: usize>(
+    protocol: &Protocol<G1>,
+    scheme: MultiOpenScheme,
+    num_statements: &[usize],
+    g1: G1Affine,
+    g2: G2Affine,
+    s_g2: G2Affine,
+) -> Result<Vec<u8>, Error> {
+    let loader = EvmLoader::new();
+
+    let mut offset = 0;
+    let statements = num_statements
+        .iter()
+        .map(|&num_statement| {
+            (0..num_statement)
+                .map(|_| {
+                    let statement = loader.calldata_load_scalar(offset);
+                    offset += 0x20;
+                    statement
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut transcript = EvmTranscript::new(loader.clone(), offset);
+    let mut strategy = SameCurveAccumulation::<_, _, LIMBS, BITS>::default();
+    scheme.accumulate(
+        protocol,
+        &loader,
+        statements,
+        &mut transcript,
+        &mut strategy,
+    )?;
+    strategy.decide(g1, g2, s_g2)?;
+    loader.assert_calldata_size(transcript.calldata_offset());
+
+    Ok(loader.runtime_code())
+}
+
+/// Encodes statements as 32 bytes big-endian scalars in column order,
+/// followed by the proof.
+pub fn encode_calldata(statements: &[Vec<Fr>], proof: &[u8]) -> Vec<u8> {
+    statements
+        .iter()
+        .flatten()
+        .flat_map(|statement| {
+            let bytes = fe_to_big(*statement).to_bytes_be();
+            let mut word = [0; 32];
+            word[32 - bytes.len()..].copy_from_slice(&bytes);
+            word
+        })
+        .chain(proof.iter().copied())
+        .collect()
+}
@@ -0,0 +1,107 @@
+use crate::{
+    loader::evm::loader::{EcPoint, EvmLoader, Scalar},
+    util::{Transcript, TranscriptRead},
+    Error,
+};
+use halo2_wrong::curves::bn256::G1;
+use std::rc::Rc;
+
+const KECCAK256_PREFIX_CHALLENGE: u8 = 0;
+const KECCAK256_PREFIX_POINT: u8 = 1;
+const KECCAK256_PREFIX_SCALAR: u8 = 2;
+
+/// Counterpart of [`crate::loader::native::Keccak256Transcript`] reading the
+/// proof from calldata, starting at the given offset.
+///
+/// Everything absorbed is written to a contiguous buffer placed after all
+/// other memory, which is hashed as a whole on every squeeze, so the
+/// challenges are identical to the native ones.
+pub struct EvmTranscript {
+    loader: Rc<EvmLoader>,
+    calldata_offset: usize,
+    buf_len: usize,
+}
+
+impl EvmTranscript {
+    pub fn new(loader: Rc<EvmLoader>, calldata_offset: usize) -> Self {
+        Self {
+            loader,
+            calldata_offset,
+            buf_len: 0,
+        }
+    }
+
+    /// Returns the calldata offset right after the proof read so far.
+    pub fn calldata_offset(&self) -> usize {
+        self.calldata_offset
+    }
+
+    fn absorb_prefix(&mut self, prefix: u8) {
+        self.loader
+            .code_mut()
+            .push(prefix)
+            .push_deferred(self.buf_len)
+            .mstore8();
+        self.buf_len += 1;
+    }
+
+    fn absorb_scalar(&mut self, scalar: &Scalar) {
+        self.absorb_prefix(KECCAK256_PREFIX_SCALAR);
+        self.loader.push_scalar(scalar);
+        self.loader.code_mut().push_deferred(self.buf_len).mstore();
+        self.buf_len += 0x20;
+    }
+
+    fn absorb_ec_point(&mut self, ec_point: &EcPoint) {
+        self.absorb_prefix(KECCAK256_PREFIX_POINT);
+        for offset in [0, 0x20] {
+            self.loader
+                .code_mut()
+                .push(ec_point.ptr() + offset)
+                .mload()
+                .push_deferred(self.buf_len)
+                .mstore();
+            self.buf_len += 0x20;
+        }
+    }
+}
+
+impl Transcript<G1, Rc<EvmLoader>> for EvmTranscript {
+    fn squeeze_challenge(&mut self) -> Scalar {
+        self.absorb_prefix(KECCAK256_PREFIX_CHALLENGE);
+        self.loader
+            .code_mut()
+            .push(self.loader.scalar_modulus().clone())
+            .push(self.buf_len)
+            .push_deferred(0)
+            .keccak256()
+            .r#mod();
+        self.loader.scalar_from_stack()
+    }
+
+    fn common_scalar(&mut self, scalar: &Scalar) -> Result<(), Error> {
+        self.absorb_scalar(scalar);
+        Ok(())
+    }
+
+    fn common_ec_point(&mut self, ec_point: &EcPoint) -> Result<(), Error> {
+        self.absorb_ec_point(ec_point);
+        Ok(())
+    }
+}
+
+impl TranscriptRead<G1, Rc<EvmLoader>> for EvmTranscript {
+    fn read_scalar(&mut self) -> Result<Scalar, Error> {
+        let scalar = self.loader.calldata_load_scalar(self.calldata_offset);
+        self.calldata_offset += 0x20;
+        self.absorb_scalar(&scalar);
+        Ok(scalar)
+    }
+
+    fn read_ec_point(&mut self) -> Result<EcPoint, Error> {
+        let ec_point = self.loader.calldata_load_ec_point(self.calldata_offset);
+        self.calldata_offset += 0x40;
+        self.absorb_ec_point(&ec_point);
+        Ok(ec_point)
+    }
+}
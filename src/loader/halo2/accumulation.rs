@@ -1,6 +1,6 @@
 use crate::{
     loader::{
-        halo2::loader::{Halo2Loader, Scalar},
+        halo2::loader::{Halo2Loader, Scalar, Value},
         LoadedEcPoint,
     },
     protocol::Protocol,
@@ -10,24 +10,106 @@ use crate::{
 };
 use halo2_wrong::curves::CurveAffine;
 use halo2_wrong_ecc::AssignedPoint;
+use halo2_wrong_maingate::AssignedValue;
 use std::rc::Rc;
 
+type DetachedScalar<C> =
+    Value<<C as CurveAffine>::ScalarExt, AssignedValue<<C as CurveAffine>::ScalarExt>>;
+type DetachedEcPoint<C, const LIMBS: usize, const BITS: usize> =
+    Value<C, AssignedPoint<<C as CurveAffine>::Base, <C as CurveAffine>::ScalarExt, LIMBS, BITS>>;
+
+#[derive(Clone, Debug)]
+struct DetachedMsm<C: CurveAffine, const LIMBS: usize, const BITS: usize> {
+    scalar: Option<DetachedScalar<C>>,
+    pairs: Vec<(DetachedScalar<C>, DetachedEcPoint<C, LIMBS, BITS>)>,
+}
+
+impl<C: CurveAffine, const LIMBS: usize, const BITS: usize> DetachedMsm<C, LIMBS, BITS> {
+    fn detach<'a, 'b>(msm: MSM<C::CurveExt, Rc<Halo2Loader<'a, 'b, C, LIMBS, BITS>>>) -> Self {
+        let (scalar, pairs) = msm.into_parts();
+        Self {
+            scalar: scalar.map(|scalar| scalar.value()),
+            pairs: pairs
+                .into_iter()
+                .map(|(scalar, base)| (scalar.value(), base.value()))
+                .collect(),
+        }
+    }
+
+    fn attach<'a, 'b>(
+        self,
+        loader: &Rc<Halo2Loader<'a, 'b, C, LIMBS, BITS>>,
+    ) -> MSM<C::CurveExt, Rc<Halo2Loader<'a, 'b, C, LIMBS, BITS>>> {
+        MSM::from_parts(
+            self.scalar.map(|scalar| loader.scalar(scalar)),
+            self.pairs
+                .into_iter()
+                .map(|(scalar, base)| (loader.scalar(scalar), loader.ec_point_from_value(base))),
+        )
+    }
+}
+
+/// Accumulator of a [`SameCurveAccumulation`] on a [`Halo2Loader`] detached
+/// from the loader, so it can outlive the region it was built in.
+///
+/// Attaching it to the loader of another region continues accumulating
+/// there, with its assigned cells copy constrained once used and its
+/// constants kept as constants, so they still merge in the final MSM.
+#[derive(Clone, Debug)]
+pub struct DetachedAccumulator<C: CurveAffine, const LIMBS: usize, const BITS: usize> {
+    lhs: DetachedMsm<C, LIMBS, BITS>,
+    rhs: DetachedMsm<C, LIMBS, BITS>,
+}
+
 impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize>
     SameCurveAccumulation<C::CurveExt, Rc<Halo2Loader<'a, 'b, C, LIMBS, BITS>>, LIMBS, BITS>
 {
     pub fn finalize(
         self,
         g1: C,
-    ) -> (
-        AssignedPoint<C::Base, C::Scalar, LIMBS, BITS>,
-        AssignedPoint<C::Base, C::Scalar, LIMBS, BITS>,
-    ) {
-        let (lhs, rhs) = self.accumulator.unwrap().evaluate(g1.to_curve());
-        let loader = lhs.loader();
+    ) -> Result<
         (
-            loader.ec_point_nomalize(&lhs.assigned()),
-            loader.ec_point_nomalize(&rhs.assigned()),
-        )
+            AssignedPoint<C::Base, C::Scalar, LIMBS, BITS>,
+            AssignedPoint<C::Base, C::Scalar, LIMBS, BITS>,
+        ),
+        Error,
+    > {
+        let (lhs, rhs) = self
+            .accumulator
+            .ok_or(Error::MissingAccumulator)?
+            .evaluate(g1.to_curve())?;
+        let loader = lhs.loader();
+        loader.take_error()?;
+        // Only an identity constant fails to be assigned
+        let lhs = lhs.assigned().map_err(|_| Error::IdentityAccumulator)?;
+        let rhs = rhs.assigned().map_err(|_| Error::IdentityAccumulator)?;
+        Ok((
+            loader.ec_point_nomalize(&lhs),
+            loader.ec_point_nomalize(&rhs),
+        ))
+    }
+
+    /// Detaches the accumulator from the loader, to continue accumulating in
+    /// another region with [`SameCurveAccumulation::attach`].
+    pub fn detach(self) -> Option<DetachedAccumulator<C, LIMBS, BITS>> {
+        self.accumulator.map(|accumulator| {
+            let (lhs, rhs) = accumulator.into_msms();
+            DetachedAccumulator {
+                lhs: DetachedMsm::detach(lhs),
+                rhs: DetachedMsm::detach(rhs),
+            }
+        })
+    }
+
+    pub fn attach(
+        loader: &Rc<Halo2Loader<'a, 'b, C, LIMBS, BITS>>,
+        detached: Option<DetachedAccumulator<C, LIMBS, BITS>>,
+    ) -> Self {
+        Self {
+            accumulator: detached.map(|detached| {
+                Accumulator::new(detached.lhs.attach(loader), detached.rhs.attach(loader))
+            }),
+        }
     }
 }
 
@@ -46,18 +128,33 @@ where
         loader: &Rc<Halo2Loader<'a, 'b, C, LIMBS, BITS>>,
         transcript: &mut T,
         statements: &[Vec<Scalar<'a, 'b, C, LIMBS, BITS>>],
-    ) -> Option<Accumulator<C::CurveExt, Rc<Halo2Loader<'a, 'b, C, LIMBS, BITS>>>> {
-        let accumulator_indices = protocol.accumulator_indices.as_ref()?;
+    ) -> Result<Option<Accumulator<C::CurveExt, Rc<Halo2Loader<'a, 'b, C, LIMBS, BITS>>>>, Error>
+    {
+        let accumulator_indices = match protocol.accumulator_indices.as_ref() {
+            Some(accumulator_indices) => accumulator_indices,
+            None => return Ok(None),
+        };
 
         let challenges = transcript.squeeze_n_challenges(accumulator_indices.len());
         let accumulators = accumulator_indices
             .iter()
             .map(|indices| {
-                assert_eq!(indices.len(), 4 * LIMBS);
+                if indices.len() != 4 * LIMBS {
+                    return Err(Error::InvalidAccumulatorIndices {
+                        expected: 4 * LIMBS,
+                        actual: indices.len(),
+                    });
+                }
                 let assinged = indices
                     .iter()
-                    .map(|index| statements[index.0][index.1].assigned())
-                    .collect::<Vec<_>>();
+                    .map(|&(column, row)| {
+                        statements
+                            .get(column)
+                            .and_then(|statements| statements.get(row))
+                            .map(|statement| statement.assigned())
+                            .ok_or(Error::MissingStatement { column, row })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
                 let lhs = loader.assign_ec_point_from_limbs(
                     assinged[..LIMBS].to_vec().try_into().unwrap(),
                     assinged[LIMBS..2 * LIMBS].to_vec().try_into().unwrap(),
@@ -66,13 +163,13 @@ where
                     assinged[2 * LIMBS..3 * LIMBS].to_vec().try_into().unwrap(),
                     assinged[3 * LIMBS..].to_vec().try_into().unwrap(),
                 );
-                Accumulator::new(MSM::base(lhs), MSM::base(rhs))
+                Ok(Accumulator::new(MSM::base(lhs), MSM::base(rhs)))
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, Error>>()?;
 
-        Some(Accumulator::random_linear_combine(
+        Ok(Some(Accumulator::random_linear_combine(
             challenges.into_iter().map(Some).zip(accumulators),
-        ))
+        )))
     }
 
     fn process(
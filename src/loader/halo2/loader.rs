@@ -1,9 +1,13 @@
 use crate::{
     loader::{EcPointLoader, LoadedEcPoint, LoadedScalar, Loader, ScalarLoader},
-    util::{Curve, Field, FieldOps, Group},
+    util::{
+        big_to_fe, fe_to_big, modulus, Curve, Field, FieldOps, Group, GroupEncoding,
+        PrimeCurveAffine,
+    },
+    Error,
 };
 use halo2_wrong::curves::CurveAffine;
-use halo2_wrong::halo2::circuit;
+use halo2_wrong::halo2::{circuit, plonk};
 use halo2_wrong_ecc::{
     integer::{
         rns::{Integer, Rns},
@@ -15,9 +19,10 @@ use halo2_wrong_maingate::{
     AssignedValue, CombinationOptionCommon, MainGate, MainGateInstructions, RegionCtx,
     Term,
 };
-use rand::rngs::OsRng;
+use num_bigint::BigUint;
 use std::{
     cell::RefCell,
+    collections::{BTreeSet, HashMap},
     fmt::{self, Debug},
     iter,
     ops::{Add, AddAssign, Deref, DerefMut, Mul, MulAssign, Neg, Sub, SubAssign},
@@ -33,33 +38,163 @@ pub enum Value<T, L> {
     Assigned(L),
 }
 
+/// Domain tag [`default_aux_generator`] is derived from.
+const AUX_GENERATOR_DOMAIN: &[u8] = b"plonk-verifier/halo2-loader/aux-generator";
+
+/// Returns the aux generator [`Halo2Loader::new`] uses, a point derived from
+/// a fixed domain tag, so witnesses are reproducible across keygen and
+/// proving while its discrete log stays unknown.
+///
+/// It hashes the domain tag with an increasing little-endian `u64` counter
+/// by Blake2b-512 into a base field `x`, until `x^3 + b` is a square, and
+/// takes the even `y` of the two roots. So the point is never a known
+/// multiple of the generator.
+pub fn default_aux_generator<C: CurveAffine>() -> C {
+    (0u64..)
+        .find_map(|counter| {
+            let hash = blake2b_simd::Params::new()
+                .to_state()
+                .update(AUX_GENERATOR_DOMAIN)
+                .update(&counter.to_le_bytes())
+                .finalize();
+            let x = big_to_fe::<C::Base>(
+                BigUint::from_bytes_le(hash.as_bytes()) % modulus::<C::Base>(),
+            );
+            let y = Option::<C::Base>::from((x.square() * x + C::b()).sqrt())?;
+            let y = if fe_to_big(y).bit(0) { -y } else { y };
+            Option::from(C::from_xy(x, y))
+        })
+        .unwrap()
+}
+
+/// Assignments of a [`Halo2Loader`] detached from its region, which are the
+/// aux generator, the aux points of MSMs and the assigned constant points.
+///
+/// Attaching it to the loader of another region reuses them there, copy
+/// constrained once used, instead of assigning them again, and keeps loads of
+/// the same constant point comparing equal across regions.
+#[derive(Clone)]
+pub struct DetachedHalo2Loader<C: CurveAffine, const LIMBS: usize, const BITS: usize> {
+    ecc_chip: BaseFieldEccChip<C, LIMBS, BITS>,
+    num_ec_point: usize,
+    const_ec_point_indices: HashMap<Vec<u8>, usize>,
+    assigned_const_ec_points: HashMap<usize, AssignedPoint<C::Base, C::Scalar, LIMBS, BITS>>,
+    aux_generator: C,
+    num_aux_pairs: BTreeSet<usize>,
+}
+
 pub struct Halo2Loader<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize> {
     rns: Rc<Rns<C::Base, C::Scalar, LIMBS, BITS>>,
     ecc_chip: RefCell<BaseFieldEccChip<C, LIMBS, BITS>>,
     main_gate: MainGate<C::Scalar>,
     ctx: RefCell<RegionCtx<'a, 'b, C::Scalar>>,
     num_ec_point: RefCell<usize>,
-    #[cfg(test)]
+    const_ec_point_indices: RefCell<HashMap<Vec<u8>, usize>>,
+    assigned_const_ec_points:
+        RefCell<HashMap<usize, AssignedPoint<C::Base, C::Scalar, LIMBS, BITS>>>,
+    aux_generator: C,
+    num_aux_pairs: RefCell<BTreeSet<usize>>,
     row_meterings: RefCell<Vec<(String, usize)>>,
+    open_row_meterings: RefCell<Vec<usize>>,
+    error: RefCell<Option<Error>>,
 }
 
 impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize>
     Halo2Loader<'a, 'b, C, LIMBS, BITS>
 {
     pub fn new(ecc_config: EccConfig, ctx: RegionCtx<'a, 'b, C::Scalar>) -> Rc<Self> {
-        let ecc_chip = BaseFieldEccChip::new(ecc_config);
-        let main_gate = ecc_chip.main_gate();
+        Self::new_with_aux_generator(ecc_config, ctx, default_aux_generator())
+    }
+
+    /// Like [`Halo2Loader::new`], but randomizes MSMs with the given
+    /// `aux_generator`, whose discrete log must be unknown to the prover.
+    pub fn new_with_aux_generator(
+        ecc_config: EccConfig,
+        ctx: RegionCtx<'a, 'b, C::Scalar>,
+        aux_generator: C,
+    ) -> Rc<Self> {
+        Self::attach_detached(
+            ctx,
+            DetachedHalo2Loader {
+                ecc_chip: BaseFieldEccChip::new(ecc_config),
+                num_ec_point: 0,
+                const_ec_point_indices: HashMap::new(),
+                assigned_const_ec_points: HashMap::new(),
+                aux_generator,
+                num_aux_pairs: BTreeSet::new(),
+            },
+        )
+    }
+
+    /// Detaches the assignments of the loader that outlive its region, to
+    /// reuse them in another region with [`Halo2Loader::attach`].
+    pub fn detach(&self) -> DetachedHalo2Loader<C, LIMBS, BITS> {
+        DetachedHalo2Loader {
+            ecc_chip: self.ecc_chip.borrow().clone(),
+            num_ec_point: *self.num_ec_point.borrow(),
+            const_ec_point_indices: self.const_ec_point_indices.borrow().clone(),
+            assigned_const_ec_points: self.assigned_const_ec_points.borrow().clone(),
+            aux_generator: self.aux_generator,
+            num_aux_pairs: self.num_aux_pairs.borrow().clone(),
+        }
+    }
+
+    /// Creates a loader for the region of `ctx` carrying on from the loader
+    /// `detached` from another region, or like [`Halo2Loader::new`] if there
+    /// is none.
+    pub fn attach(
+        ecc_config: EccConfig,
+        ctx: RegionCtx<'a, 'b, C::Scalar>,
+        detached: Option<DetachedHalo2Loader<C, LIMBS, BITS>>,
+    ) -> Rc<Self> {
+        match detached {
+            Some(detached) => Self::attach_detached(ctx, detached),
+            None => Self::new(ecc_config, ctx),
+        }
+    }
+
+    fn attach_detached(
+        ctx: RegionCtx<'a, 'b, C::Scalar>,
+        detached: DetachedHalo2Loader<C, LIMBS, BITS>,
+    ) -> Rc<Self> {
+        let main_gate = detached.ecc_chip.main_gate();
         Rc::new(Self {
             rns: Rc::new(Rns::construct()),
-            ecc_chip: RefCell::new(ecc_chip),
+            ecc_chip: RefCell::new(detached.ecc_chip),
             main_gate,
             ctx: RefCell::new(ctx),
-            num_ec_point: RefCell::new(0),
-            #[cfg(test)]
+            num_ec_point: RefCell::new(detached.num_ec_point),
+            const_ec_point_indices: RefCell::new(detached.const_ec_point_indices),
+            assigned_const_ec_points: RefCell::new(detached.assigned_const_ec_points),
+            aux_generator: detached.aux_generator,
+            num_aux_pairs: RefCell::new(detached.num_aux_pairs),
             row_meterings: RefCell::new(Vec::new()),
+            open_row_meterings: RefCell::new(Vec::new()),
+            error: RefCell::new(None),
         })
     }
 
+    /// Returns the first error of the ecc chip hit by point arithmetic through
+    /// operators, which can't return it themselves.
+    pub fn take_error(&self) -> Result<(), Error> {
+        match self.error.borrow_mut().take() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Records `err` unless an earlier one is pending, and gives the identity
+    /// constant in place of the point that failed, so the operator can go on
+    /// until [`Halo2Loader::take_error`] reports it.
+    fn record_error(self: &Rc<Self>, err: Error) -> EcPoint<'a, 'b, C, LIMBS, BITS> {
+        let mut error = self.error.borrow_mut();
+        if error.is_none() {
+            *error = Some(err);
+        }
+        drop(error);
+        self.assign_const_ec_point(C::identity())
+    }
+
     pub fn rns(&self) -> Rc<Rns<C::Base, C::Scalar, LIMBS, BITS>> {
         self.rns.clone()
     }
@@ -72,6 +207,24 @@ impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize>
         self.ctx.borrow_mut()
     }
 
+    /// Assigns the aux point of a `num_pairs` MSM, together with the aux
+    /// generator on first use, unless it's been assigned already.
+    fn assign_aux(&self, num_pairs: usize) -> Result<(), plonk::Error> {
+        let mut num_aux_pairs = self.num_aux_pairs.borrow_mut();
+        if num_aux_pairs.contains(&num_pairs) {
+            return Ok(());
+        }
+
+        let mut ecc_chip = self.ecc_chip.borrow_mut();
+        let mut ctx = self.ctx.borrow_mut();
+        if num_aux_pairs.is_empty() {
+            ecc_chip.assign_aux_generator(&mut ctx, circuit::Value::known(self.aux_generator))?;
+        }
+        ecc_chip.assign_aux(&mut ctx, WINDOW_SIZE, num_pairs)?;
+        num_aux_pairs.insert(num_pairs);
+        Ok(())
+    }
+
     pub fn assign_const_scalar(
         self: &Rc<Self>,
         scalar: C::Scalar,
@@ -104,13 +257,47 @@ impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize>
         }
     }
 
+    /// Loads a constant point, which is only assigned once it's needed as an
+    /// assigned point, sharing the cells with every other load of it.
+    ///
+    /// Loads of the same point compare equal, so MSMs merge the terms of
+    /// constant bases shared between snarks of the same protocol, e.g.
+    /// preprocessed commitments and `g1`.
     pub fn assign_const_ec_point(self: &Rc<Self>, ec_point: C) -> EcPoint<'a, 'b, C, LIMBS, BITS> {
+        let index = *self
+            .const_ec_point_indices
+            .borrow_mut()
+            .entry(ec_point.to_bytes().as_ref().to_vec())
+            .or_insert_with(|| self.next_ec_point_index());
+        EcPoint {
+            loader: self.clone(),
+            index,
+            value: Value::Constant(ec_point),
+        }
+    }
+
+    /// Returns [`plonk::Error::Synthesis`] for the identity, which has no
+    /// affine coordinates to assign.
+    fn assigned_const_ec_point(
+        &self,
+        index: usize,
+        ec_point: C,
+    ) -> Result<AssignedPoint<C::Base, C::Scalar, LIMBS, BITS>, plonk::Error> {
+        if let Some(assigned) = self.assigned_const_ec_points.borrow().get(&index) {
+            return Ok(assigned.clone());
+        }
+        if bool::from(ec_point.is_identity()) {
+            return Err(plonk::Error::Synthesis);
+        }
+
         let assigned = self
             .ecc_chip
             .borrow()
-            .assign_constant(&mut self.ctx_mut(), ec_point)
-            .unwrap();
-        self.ec_point(assigned)
+            .assign_constant(&mut self.ctx_mut(), ec_point)?;
+        self.assigned_const_ec_points
+            .borrow_mut()
+            .insert(index, assigned.clone());
+        Ok(assigned)
     }
 
     pub fn assign_ec_point(
@@ -181,12 +368,86 @@ impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize>
         self: &Rc<Self>,
         assigned: AssignedPoint<C::Base, C::Scalar, LIMBS, BITS>,
     ) -> EcPoint<'a, 'b, C, LIMBS, BITS> {
-        let index = *self.num_ec_point.borrow();
-        *self.num_ec_point.borrow_mut() += 1;
         EcPoint {
             loader: self.clone(),
-            index,
-            assigned,
+            index: self.next_ec_point_index(),
+            value: Value::Assigned(assigned),
+        }
+    }
+
+    /// Loads a point given by the value of one loaded by any loader, e.g. of
+    /// another region, whose cells get copy constrained once used here.
+    pub(super) fn ec_point_from_value(
+        self: &Rc<Self>,
+        value: Value<C, AssignedPoint<C::Base, C::Scalar, LIMBS, BITS>>,
+    ) -> EcPoint<'a, 'b, C, LIMBS, BITS> {
+        match value {
+            Value::Constant(constant) => self.assign_const_ec_point(constant),
+            Value::Assigned(assigned) => self.ec_point(assigned),
+        }
+    }
+
+    fn next_ec_point_index(&self) -> usize {
+        let index = *self.num_ec_point.borrow();
+        *self.num_ec_point.borrow_mut() += 1;
+        index
+    }
+
+    /// Adds `lhs` and `rhs`, folding constant operands natively.
+    ///
+    /// Identity constants are skipped, loads of the same point are doubled
+    /// and subtracting a point from itself gives the identity constant.
+    /// Otherwise the incomplete addition of the ecc chip is used, which is
+    /// unsatisfiable if the operands happen to be equal or opposite.
+    pub fn ec_point_add(
+        self: &Rc<Self>,
+        lhs: &EcPoint<'a, 'b, C, LIMBS, BITS>,
+        rhs: &EcPoint<'a, 'b, C, LIMBS, BITS>,
+    ) -> Result<EcPoint<'a, 'b, C, LIMBS, BITS>, Error> {
+        match (&lhs.value, &rhs.value) {
+            (Value::Constant(lhs), Value::Constant(rhs)) => {
+                Ok(self.assign_const_ec_point((lhs.to_curve() + rhs.to_curve()).to_affine()))
+            }
+            (Value::Constant(constant), _) if bool::from(constant.is_identity()) => Ok(rhs.clone()),
+            (_, Value::Constant(constant)) if bool::from(constant.is_identity()) => Ok(lhs.clone()),
+            _ if lhs == rhs => {
+                let lhs = lhs.assigned()?;
+                let output = self.ecc_chip().double(&mut self.ctx_mut(), &lhs)?;
+                Ok(self.ec_point(output))
+            }
+            _ => {
+                // The ecc chip has no gadget for adding a constant point, so
+                // a constant operand is assigned as a point, once, and its
+                // cells are reused by every addition with it, which costs as
+                // many rows as adding two assigned points
+                let (lhs, rhs) = (lhs.assigned()?, rhs.assigned()?);
+                let output = self.ecc_chip().add(&mut self.ctx_mut(), &lhs, &rhs)?;
+                Ok(self.ec_point(output))
+            }
+        }
+    }
+
+    pub fn ec_point_sub(
+        self: &Rc<Self>,
+        lhs: &EcPoint<'a, 'b, C, LIMBS, BITS>,
+        rhs: &EcPoint<'a, 'b, C, LIMBS, BITS>,
+    ) -> Result<EcPoint<'a, 'b, C, LIMBS, BITS>, Error> {
+        if lhs == rhs {
+            return Ok(self.assign_const_ec_point(C::identity()));
+        }
+        self.ec_point_add(lhs, &self.ec_point_neg(rhs)?)
+    }
+
+    pub fn ec_point_neg(
+        self: &Rc<Self>,
+        ec_point: &EcPoint<'a, 'b, C, LIMBS, BITS>,
+    ) -> Result<EcPoint<'a, 'b, C, LIMBS, BITS>, Error> {
+        match &ec_point.value {
+            Value::Constant(constant) => Ok(self.assign_const_ec_point(-*constant)),
+            Value::Assigned(assigned) => {
+                let output = self.ecc_chip().neg(&mut self.ctx_mut(), assigned)?;
+                Ok(self.ec_point(output))
+            }
         }
     }
 
@@ -332,26 +593,38 @@ impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize>
     }
 }
 
-#[cfg(test)]
 impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize>
     Halo2Loader<'a, 'b, C, LIMBS, BITS>
 {
     fn start_row_metering(self: &Rc<Self>, identifier: &str) {
-        self.row_meterings
+        let mut row_meterings = self.row_meterings.borrow_mut();
+        self.open_row_meterings
             .borrow_mut()
-            .push((identifier.to_string(), *self.ctx.borrow().offset))
+            .push(row_meterings.len());
+        row_meterings.push((identifier.to_string(), *self.ctx.borrow().offset));
     }
 
+    /// Ends the most recently started section.
+    ///
+    /// Ending one with none left open is a bug of the caller, which is
+    /// asserted in debug builds and ignored otherwise.
     fn end_row_metering(self: &Rc<Self>) {
-        let mut row_meterings = self.row_meterings.borrow_mut();
-        let (_, row) = row_meterings.last_mut().unwrap();
-        *row = *self.ctx.borrow().offset - *row;
+        let idx = self.open_row_meterings.borrow_mut().pop();
+        debug_assert!(idx.is_some(), "Cost metering ended without being started");
+        if let Some(idx) = idx {
+            let (_, row) = &mut self.row_meterings.borrow_mut()[idx];
+            *row = *self.ctx.borrow().offset - *row;
+        }
     }
 
-    pub fn print_row_metering(self: &Rc<Self>) {
-        for (identifier, cost) in self.row_meterings.borrow().iter() {
-            println!("{}: {}", identifier, cost);
-        }
+    /// Returns the number of rows taken by each section metered with
+    /// [`Loader::start_cost_metering`] and [`Loader::end_cost_metering`] so
+    /// far, in the order the sections were started.
+    ///
+    /// Sections might be nested, in which case the outer one also counts the
+    /// rows of the inner ones.
+    pub fn row_meterings(&self) -> Vec<(String, usize)> {
+        self.row_meterings.borrow().clone()
     }
 }
 
@@ -362,6 +635,10 @@ pub struct Scalar<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize>
 }
 
 impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize> Scalar<'a, 'b, C, LIMBS, BITS> {
+    pub(super) fn value(&self) -> Value<C::Scalar, AssignedValue<C::Scalar>> {
+        self.value.clone()
+    }
+
     pub fn assigned(&self) -> AssignedValue<C::Scalar> {
         match &self.value {
             Value::Constant(constant) => self.loader.assign_const_scalar(*constant).assigned(),
@@ -520,14 +797,23 @@ impl<'a, 'b, 'c, C: CurveAffine, const LIMBS: usize, const BITS: usize> MulAssig
 pub struct EcPoint<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize> {
     loader: Rc<Halo2Loader<'a, 'b, C, LIMBS, BITS>>,
     index: usize,
-    assigned: AssignedPoint<C::Base, C::Scalar, LIMBS, BITS>,
+    value: Value<C, AssignedPoint<C::Base, C::Scalar, LIMBS, BITS>>,
 }
 
 impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize>
     EcPoint<'a, 'b, C, LIMBS, BITS>
 {
-    pub fn assigned(&self) -> AssignedPoint<C::Base, C::Scalar, LIMBS, BITS> {
-        self.assigned.clone()
+    pub(super) fn value(&self) -> Value<C, AssignedPoint<C::Base, C::Scalar, LIMBS, BITS>> {
+        self.value.clone()
+    }
+
+    /// Returns the assigned point, assigning a constant on first use, or
+    /// [`plonk::Error::Synthesis`] if it's the identity constant.
+    pub fn assigned(&self) -> Result<AssignedPoint<C::Base, C::Scalar, LIMBS, BITS>, plonk::Error> {
+        match &self.value {
+            Value::Constant(constant) => self.loader.assigned_const_ec_point(self.index, *constant),
+            Value::Assigned(assigned) => Ok(assigned.clone()),
+        }
     }
 }
 
@@ -550,57 +836,56 @@ impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize> LoadedEcPoin
 
     fn multi_scalar_multiplication(
         pairs: impl IntoIterator<Item = (Scalar<'a, 'b, C, LIMBS, BITS>, Self)>,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         let pairs = pairs.into_iter().collect::<Vec<_>>();
-        let loader = &pairs[0].0.loader;
-
-        let (non_scaled, scaled) = pairs.iter().fold(
-            (Vec::new(), Vec::new()),
-            |(mut non_scaled, mut scaled), (scalar, ec_point)| {
-                if matches!(scalar.value, Value::Constant(constant) if constant == C::Scalar::one())
-                {
-                    non_scaled.push(ec_point.assigned());
-                } else {
-                    scaled.push((ec_point.assigned(), scalar.assigned()))
+        let loader = &pairs.first().ok_or(Error::EmptyMsm)?.0.loader;
+
+        let mut constant = C::CurveExt::identity();
+        let mut non_scaled = Vec::new();
+        let mut scaled = Vec::new();
+        for (scalar, ec_point) in pairs.iter() {
+            match (&scalar.value, &ec_point.value) {
+                (Value::Constant(scalar), _) if *scalar == C::Scalar::zero() => {}
+                (_, Value::Constant(base)) if bool::from(base.is_identity()) => {}
+                (Value::Constant(scalar), Value::Constant(base)) => constant += *base * *scalar,
+                (Value::Constant(scalar), _) if *scalar == C::Scalar::one() => {
+                    non_scaled.push(ec_point.assigned()?)
                 }
-                (non_scaled, scaled)
-            },
-        );
+                _ => scaled.push((ec_point.assigned()?, scalar.assigned())),
+            }
+        }
 
-        let output = iter::empty()
-            .chain(if scaled.is_empty() {
-                None
-            } else {
-                let aux_generator = <C as CurveAffine>::CurveExt::random(OsRng).to_affine();
-                loader
-                    .ecc_chip
-                    .borrow_mut()
-                    .assign_aux_generator(
-                        &mut loader.ctx.borrow_mut(),
-                        circuit::Value::known(aux_generator),
-                    )
-                    .unwrap();
+        if non_scaled.is_empty() && scaled.is_empty() {
+            return Ok(loader.assign_const_ec_point(constant.to_affine()));
+        }
+
+        let scaled = if scaled.is_empty() {
+            None
+        } else {
+            loader.assign_aux(scaled.len())?;
+            Some(loader.ecc_chip.borrow().mul_batch_1d_horizontal(
+                &mut loader.ctx.borrow_mut(),
+                scaled,
+                WINDOW_SIZE,
+            )?)
+        };
+        let constant = (!bool::from(constant.is_identity()))
+            .then(|| {
                 loader
-                    .ecc_chip
-                    .borrow_mut()
-                    .assign_aux(&mut loader.ctx.borrow_mut(), WINDOW_SIZE, scaled.len())
-                    .unwrap();
-                Some(
-                    loader
-                        .ecc_chip
-                        .borrow()
-                        .mul_batch_1d_horizontal(&mut loader.ctx.borrow_mut(), scaled, WINDOW_SIZE)
-                        .unwrap(),
-                )
+                    .assign_const_ec_point(constant.to_affine())
+                    .assigned()
             })
+            .transpose()?;
+
+        let mut ec_points = iter::empty()
+            .chain(scaled)
             .chain(non_scaled)
-            .reduce(|acc, ec_point| {
-                (loader.ecc_chip().deref())
-                    .add(&mut loader.ctx.borrow_mut(), &acc, &ec_point)
-                    .unwrap()
-            })
-            .unwrap();
-        loader.ec_point(output)
+            .chain(constant);
+        let first = ec_points.next().ok_or(Error::EmptyMsm)?;
+        let output = ec_points.try_fold(first, |acc, ec_point| {
+            (loader.ecc_chip().deref()).add(&mut loader.ctx.borrow_mut(), &acc, &ec_point)
+        })?;
+        Ok(loader.ec_point(output))
     }
 }
 
@@ -610,18 +895,23 @@ impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize> Debug
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("EcPoint")
             .field("index", &self.index)
-            .field("assigned", &self.assigned)
+            .field("value", &self.value)
             .finish()
     }
 }
 
+// Operators can't return the errors of the ecc chip, so they record them on
+// the loader to be returned by `Halo2Loader::take_error`, while
+// `Halo2Loader::ec_point_add` and its siblings return them directly
 impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize> Add
     for EcPoint<'a, 'b, C, LIMBS, BITS>
 {
     type Output = Self;
 
-    fn add(self, _: Self) -> Self::Output {
-        todo!()
+    fn add(self, rhs: Self) -> Self::Output {
+        self.loader
+            .ec_point_add(&self, &rhs)
+            .unwrap_or_else(|err| self.loader.record_error(err))
     }
 }
 
@@ -630,8 +920,10 @@ impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize> Sub
 {
     type Output = Self;
 
-    fn sub(self, _: Self) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.loader
+            .ec_point_sub(&self, &rhs)
+            .unwrap_or_else(|err| self.loader.record_error(err))
     }
 }
 
@@ -641,7 +933,9 @@ impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize> Neg
     type Output = Self;
 
     fn neg(self) -> Self::Output {
-        todo!()
+        self.loader
+            .ec_point_neg(&self)
+            .unwrap_or_else(|err| self.loader.record_error(err))
     }
 }
 
@@ -720,13 +1014,418 @@ impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize> EcPointLoade
 impl<'a, 'b, C: CurveAffine, const LIMBS: usize, const BITS: usize> Loader<C::CurveExt>
     for Rc<Halo2Loader<'a, 'b, C, LIMBS, BITS>>
 {
-    #[cfg(test)]
     fn start_cost_metering(&self, identifier: &str) {
         self.start_row_metering(identifier)
     }
 
-    #[cfg(test)]
     fn end_cost_metering(&self) {
         self.end_row_metering()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{default_aux_generator, EcPoint, Halo2Loader};
+    use crate::{
+        circuit::{BITS, LIMBS},
+        loader::{
+            halo2::PoseidonTranscript, native::PoseidonTranscript as NativePoseidonTranscript,
+            CostMetering, EcPointLoader, LoadedEcPoint, Loader,
+        },
+        native::Snark,
+        scheme::{MultiOpenScheme, SameCurveAccumulation},
+        test::{app_params, standard_plonk_snarks},
+        util::{fe_to_big, Curve, Field, Group, PrimeCurveAffine, Transcript},
+    };
+    use halo2_wrong::curves::{
+        bn256::{Fr, G1Affine, G1},
+        CurveAffine,
+    };
+    use halo2_wrong::halo2::{
+        circuit::{floor_planner::V1, Layouter, Value},
+        dev::MockProver,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use halo2_wrong_ecc::{BaseFieldEccChip, EccConfig};
+    use halo2_wrong_maingate::{
+        MainGate, MainGateConfig, RangeChip, RangeConfig, RangeInstructions, RegionCtx,
+    };
+    use halo2_wrong_transcript::NativeRepresentation;
+    use rand::rngs::OsRng;
+    use std::{cell::Cell, rc::Rc};
+
+    const K: u32 = 20;
+
+    type Synthesize = Rc<dyn for<'a, 'b> Fn(&Rc<Halo2Loader<'a, 'b, G1Affine, LIMBS, BITS>>)>;
+
+    /// Runs `synthesize` with a loader in a single region.
+    struct LoaderCircuit {
+        synthesize: Synthesize,
+        num_rows: Cell<usize>,
+    }
+
+    impl Circuit<Fr> for LoaderCircuit {
+        type Config = (MainGateConfig, RangeConfig);
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                synthesize: self.synthesize.clone(),
+                num_rows: Cell::new(0),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let main_gate_config = MainGate::<Fr>::configure(meta);
+            let range_config = RangeChip::<Fr>::configure(
+                meta,
+                &main_gate_config,
+                BaseFieldEccChip::<G1Affine, LIMBS, BITS>::rns().overflow_lengths(),
+                vec![BITS / LIMBS],
+            );
+            (main_gate_config, range_config)
+        }
+
+        fn synthesize(
+            &self,
+            (main_gate_config, range_config): Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let range_chip = RangeChip::<Fr>::new(range_config.clone());
+            range_chip.load_composition_tables(&mut layouter)?;
+            range_chip.load_overflow_tables(&mut layouter)?;
+
+            layouter.assign_region(
+                || "",
+                |mut region| {
+                    let mut offset = 0;
+                    let ctx = RegionCtx::new(&mut region, &mut offset);
+                    let loader = Halo2Loader::<G1Affine, LIMBS, BITS>::new(
+                        EccConfig::new(range_config.clone(), main_gate_config.clone()),
+                        ctx,
+                    );
+                    (self.synthesize)(&loader);
+                    loader.take_error()?;
+                    self.num_rows.set(*loader.ctx.borrow().offset);
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    /// Runs `synthesize` on [`MockProver`], asserting the constraints are
+    /// satisfied, and returns the number of rows it took.
+    fn mock_synthesize(
+        synthesize: impl for<'a, 'b> Fn(&Rc<Halo2Loader<'a, 'b, G1Affine, LIMBS, BITS>>) + 'static,
+    ) -> usize {
+        let circuit = LoaderCircuit {
+            synthesize: Rc::new(synthesize),
+            num_rows: Cell::new(0),
+        };
+        MockProver::run(K, &circuit, Vec::new())
+            .unwrap()
+            .assert_satisfied();
+        circuit.num_rows.get()
+    }
+
+    /// Constrains `ec_point` to equal `expected`.
+    fn assert_ec_point_eq(ec_point: &EcPoint<G1Affine, LIMBS, BITS>, expected: G1) {
+        let loader = &ec_point.loader;
+        let [ec_point, expected] = [
+            ec_point.clone(),
+            loader.assign_const_ec_point(expected.to_affine()),
+        ]
+        .map(|ec_point| ec_point.assigned().unwrap());
+        loader
+            .ecc_chip()
+            .assert_equal(&mut loader.ctx_mut(), &ec_point, &expected)
+            .unwrap();
+    }
+
+    fn assert_constant_eq(ec_point: &EcPoint<G1Affine, LIMBS, BITS>, expected: G1) {
+        assert!(matches!(
+            ec_point.value,
+            super::Value::Constant(constant) if constant == expected.to_affine()
+        ));
+    }
+
+    /// Accumulates `snarks` with a loader, returning the number of constant
+    /// and assigned bases left in the accumulator.
+    fn num_accumulator_bases(snarks: Vec<Snark<G1>>) -> (usize, usize) {
+        let num_bases = Rc::new(Cell::new((0, 0)));
+        let output = num_bases.clone();
+        mock_synthesize(move |loader| {
+            let mut strategy = SameCurveAccumulation::<
+                G1,
+                Rc<Halo2Loader<G1Affine, LIMBS, BITS>>,
+                LIMBS,
+                BITS,
+            >::default();
+            for snark in snarks.iter() {
+                let mut transcript =
+                    PoseidonTranscript::<G1Affine, _, NativeRepresentation, LIMBS, BITS>::new(
+                        loader,
+                        Value::known(snark.proof.as_slice()),
+                    );
+                let statements = snark
+                    .statements
+                    .iter()
+                    .map(|statements| {
+                        statements
+                            .iter()
+                            .map(|statement| loader.assign_scalar(Value::known(*statement)))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+                snark
+                    .scheme
+                    .accumulate(
+                        &snark.protocol,
+                        loader,
+                        statements,
+                        &mut transcript,
+                        &mut strategy,
+                    )
+                    .unwrap();
+            }
+
+            let (lhs, rhs) = strategy.accumulator.unwrap().into_msms();
+            let bases = [lhs, rhs]
+                .into_iter()
+                .flat_map(|msm| msm.into_parts().1)
+                .map(|(_, base)| base)
+                .collect::<Vec<_>>();
+            let num_constant = bases
+                .iter()
+                .filter(|base| matches!(base.value, super::Value::Constant(_)))
+                .count();
+            output.set((num_constant, bases.len() - num_constant));
+        });
+        num_bases.get()
+    }
+
+    #[test]
+    fn should_derive_aux_generator_deterministically() {
+        let aux_generator = default_aux_generator::<G1Affine>();
+        assert_eq!(aux_generator, default_aux_generator::<G1Affine>());
+
+        let coordinates = aux_generator.coordinates().unwrap();
+        assert_eq!(
+            [*coordinates.x(), *coordinates.y()].map(|fe| fe_to_big(fe).to_string()),
+            [
+                "1893948605173313820556987852880440636117720760705319518122382539907185672438",
+                "6712148511352159574509175479158344863643250733642007443133348853254763156070",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_end_cost_metering_left_on_error() {
+        mock_synthesize(|loader| {
+            let section = || -> Result<(), crate::Error> {
+                let _outer = CostMetering::<G1, _>::start(loader, "outer");
+                let _inner = CostMetering::<G1, _>::start(loader, "inner");
+                Err(crate::Error::ProofTooShort)
+            };
+            assert!(section().is_err());
+
+            assert!(loader.open_row_meterings.borrow().is_empty());
+            let sections = loader
+                .row_meterings()
+                .into_iter()
+                .map(|(identifier, _)| identifier)
+                .collect::<Vec<_>>();
+            assert_eq!(sections, ["outer", "inner"]);
+        });
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Cost metering ended without being started")]
+    fn should_assert_end_cost_metering_has_start() {
+        mock_synthesize(|loader| {
+            let metering = CostMetering::<G1, _>::start(loader, "section");
+            drop(metering);
+            Loader::<G1>::end_cost_metering(loader);
+        });
+    }
+
+    #[test]
+    fn should_fold_constant_ec_point_arithmetic() {
+        let [lhs, rhs] = [(); 2].map(|_| G1::random(OsRng));
+        let num_rows = mock_synthesize(move |loader| {
+            let [lhs_loaded, rhs_loaded] =
+                [lhs, rhs].map(|ec_point| loader.assign_const_ec_point(ec_point.to_affine()));
+            let outputs = [
+                lhs_loaded.clone() + &rhs_loaded,
+                lhs_loaded.clone() - &rhs_loaded,
+                -lhs_loaded.clone(),
+                lhs_loaded.clone() - &lhs_loaded,
+            ];
+            let expected = [lhs + rhs, lhs - rhs, -lhs, G1::identity()];
+            for (output, expected) in outputs.iter().zip(expected) {
+                assert_constant_eq(output, expected);
+            }
+        });
+        assert_eq!(num_rows, 0);
+    }
+
+    #[test]
+    fn should_add_constant_and_assigned_ec_points() {
+        let [constant, assigned] = [(); 2].map(|_| G1::random(OsRng));
+        mock_synthesize(move |loader| {
+            let constant_loaded = loader.assign_const_ec_point(constant.to_affine());
+            let assigned_loaded = loader.assign_ec_point(Value::known(assigned.to_affine()));
+            assert_ec_point_eq(
+                &(constant_loaded.clone() + &assigned_loaded),
+                constant + assigned,
+            );
+            assert_ec_point_eq(
+                &(assigned_loaded.clone() + &constant_loaded),
+                assigned + constant,
+            );
+            assert_ec_point_eq(
+                &(constant_loaded.clone() - &assigned_loaded),
+                constant - assigned,
+            );
+            assert_ec_point_eq(
+                &(assigned_loaded.clone() - &constant_loaded),
+                assigned - constant,
+            );
+            assert_ec_point_eq(&-assigned_loaded, -assigned);
+        });
+    }
+
+    #[test]
+    fn should_add_degenerate_ec_points() {
+        let ec_point = G1::random(OsRng);
+        mock_synthesize(move |loader| {
+            let identity = loader.ec_point_load_zero();
+            for loaded in [
+                loader.assign_const_ec_point(ec_point.to_affine()),
+                loader.assign_ec_point(Value::known(ec_point.to_affine())),
+            ] {
+                assert_ec_point_eq(&(loaded.clone() + &loaded), ec_point.double());
+                assert_ec_point_eq(&(loaded.clone() + &identity), ec_point);
+                assert_ec_point_eq(&(identity.clone() + &loaded), ec_point);
+                assert_ec_point_eq(&(loaded.clone() - &identity), ec_point);
+                assert_constant_eq(&(loaded.clone() - &loaded), G1::identity());
+            }
+            let constant = loader.assign_const_ec_point(ec_point.to_affine());
+            assert_constant_eq(&(constant.clone() + &-constant), G1::identity());
+        });
+    }
+
+    #[test]
+    fn should_report_identity_constant_instead_of_assigning_it() {
+        let ec_point = G1::random(OsRng);
+        mock_synthesize(move |loader| {
+            let constant = loader.assign_const_ec_point(ec_point.to_affine());
+            let identity = constant.clone() - &constant;
+            assert!(identity.assigned().is_err());
+
+            let mut transcript =
+                PoseidonTranscript::<G1Affine, _, NativeRepresentation, LIMBS, BITS>::new(
+                    loader,
+                    Value::known([].as_slice()),
+                );
+            assert!(transcript.common_ec_point(&identity).is_err());
+        });
+    }
+
+    #[test]
+    fn should_squeeze_same_challenges_as_native_poseidon_transcript() {
+        let scalars = [(); 2].map(|_| Fr::random(OsRng));
+        let ec_points = [(); 2].map(|_| G1::random(OsRng));
+
+        let expected = {
+            let mut transcript = NativePoseidonTranscript::<G1Affine, _>::new(Vec::<u8>::new());
+            let mut challenges = Vec::new();
+            for (scalar, ec_point) in scalars.iter().zip(ec_points.iter()) {
+                transcript.common_ec_point(ec_point).unwrap();
+                transcript.common_scalar(scalar).unwrap();
+                challenges.push(transcript.squeeze_challenge());
+            }
+            challenges.push(transcript.squeeze_challenge());
+            challenges
+        };
+
+        mock_synthesize(move |loader| {
+            let mut transcript =
+                PoseidonTranscript::<G1Affine, _, NativeRepresentation, LIMBS, BITS>::new(
+                    loader,
+                    Value::known([].as_slice()),
+                );
+            let mut challenges = Vec::new();
+            for (scalar, ec_point) in scalars.iter().zip(ec_points.iter()) {
+                let ec_point = loader.assign_ec_point(Value::known(ec_point.to_affine()));
+                let scalar = loader.assign_scalar(Value::known(*scalar));
+                transcript.common_ec_point(&ec_point).unwrap();
+                transcript.common_scalar(&scalar).unwrap();
+                challenges.push(transcript.squeeze_challenge());
+            }
+            challenges.push(transcript.squeeze_challenge());
+
+            assert_eq!(challenges.len(), expected.len());
+            for (challenge, expected) in challenges.iter().zip(expected.iter()) {
+                challenge
+                    .assigned()
+                    .value()
+                    .assert_if_known(|challenge| *challenge == expected);
+            }
+        });
+    }
+
+    #[test]
+    fn should_multiply_constant_base_by_zero_one_and_random_scalar() {
+        let base = G1::random(OsRng);
+        let other_base = G1::random(OsRng);
+        let other_scalar = Fr::random(OsRng);
+        let skipped_scalar = Fr::random(OsRng);
+        let skipped_base = G1::random(OsRng).to_affine();
+        for scalar in [Fr::zero(), Fr::one(), Fr::random(OsRng)] {
+            mock_synthesize(move |loader| {
+                let output = EcPoint::multi_scalar_multiplication([
+                    (
+                        loader.assign_scalar(Value::known(scalar)),
+                        loader.assign_const_ec_point(base.to_affine()),
+                    ),
+                    (
+                        loader.assign_scalar(Value::known(other_scalar)),
+                        loader.assign_const_ec_point(other_base.to_affine()),
+                    ),
+                    // Both skipped, as their terms are the identity
+                    (
+                        loader.assign_scalar(Value::known(skipped_scalar)),
+                        loader.assign_const_ec_point(G1Affine::identity()),
+                    ),
+                    (
+                        loader.scalar(super::Value::Constant(Fr::zero())),
+                        loader.assign_ec_point(Value::known(skipped_base)),
+                    ),
+                ])
+                .unwrap();
+                assert_ec_point_eq(&output, base * scalar + other_base * other_scalar);
+            });
+        }
+    }
+
+    #[test]
+    fn should_merge_constant_bases_of_snarks_sharing_protocol() {
+        let params = app_params();
+        let mut snarks = standard_plonk_snarks::<NativePoseidonTranscript<_, _>>(
+            &params,
+            &[MultiOpenScheme::Shplonk; 3],
+        );
+        let pair = snarks.split_off(1);
+
+        let (num_constant, num_assigned) = num_accumulator_bases(snarks);
+        assert!(num_constant > 0);
+        // Preprocessed commitments are loaded once however many snarks share
+        // them, while proof commitments of each snark stay apart
+        assert_eq!(
+            num_accumulator_bases(pair),
+            (num_constant, 2 * num_assigned)
+        );
+    }
+}
@@ -2,5 +2,8 @@ mod accumulation;
 mod loader;
 mod transcript;
 
-pub use loader::Halo2Loader;
+pub use accumulation::DetachedAccumulator;
+pub use loader::{default_aux_generator, DetachedHalo2Loader, Halo2Loader};
 pub use transcript::PoseidonTranscript;
+
+pub(crate) use transcript::{RATE, R_F, R_P, T};
@@ -10,15 +10,15 @@ use halo2_wrong::halo2::circuit;
 use halo2_wrong_transcript::{PointRepresentation, TranscriptChip};
 use poseidon::Spec;
 use std::{
-    io::Read,
+    io::{self, Read},
     marker::PhantomData,
     rc::Rc,
 };
 
-const T: usize = 5;
-const RATE: usize = 4;
-const R_F: usize = 8;
-const R_P: usize = 57;
+pub(crate) const T: usize = 5;
+pub(crate) const RATE: usize = 4;
+pub(crate) const R_F: usize = 8;
+pub(crate) const R_P: usize = 57;
 
 pub struct PoseidonTranscript<
     'a,
@@ -103,10 +103,12 @@ impl<
     }
 
     fn common_ec_point(&mut self, ec_point: &EcPoint<'a, 'b, C, LIMBS, BITS>) -> Result<(), Error> {
+        let ec_point = ec_point
+            .assigned()
+            .map_err(|err| Error::Transcript(io::ErrorKind::Other, format!("{:?}", err)))?;
         self.buf
-            .write_point(&mut self.loader.ctx_mut(), &ec_point.assigned())
-            .unwrap();
-        Ok(())
+            .write_point(&mut self.loader.ctx_mut(), &ec_point)
+            .map_err(|err| Error::Transcript(io::ErrorKind::Other, format!("{:?}", err)))
     }
 }
 
@@ -130,30 +132,46 @@ impl<
     >
 {
     fn read_scalar(&mut self) -> Result<Scalar<'a, 'b, C, LIMBS, BITS>, Error> {
+        let mut error = None;
         let scalar = self.stream.as_mut().and_then(|stream| {
             let mut data = <C::Scalar as PrimeField>::Repr::default();
-            if stream.read_exact(data.as_mut()).is_err() {
+            if let Err(err) = stream.read_exact(data.as_mut()) {
+                error = Some(Error::from(err));
                 return circuit::Value::unknown();
             }
             Option::<C::Scalar>::from(C::Scalar::from_repr(data))
                 .map(circuit::Value::known)
-                .unwrap_or_else(circuit::Value::unknown)
+                .unwrap_or_else(|| {
+                    error = Some(Error::InvalidScalarEncoding);
+                    circuit::Value::unknown()
+                })
         });
+        if let Some(error) = error {
+            return Err(error);
+        }
         let scalar = self.loader.assign_scalar(scalar);
         self.common_scalar(&scalar)?;
         Ok(scalar)
     }
 
     fn read_ec_point(&mut self) -> Result<EcPoint<'a, 'b, C, LIMBS, BITS>, Error> {
+        let mut error = None;
         let ec_point = self.stream.as_mut().and_then(|stream| {
             let mut compressed = C::Repr::default();
-            if stream.read_exact(compressed.as_mut()).is_err() {
+            if let Err(err) = stream.read_exact(compressed.as_mut()) {
+                error = Some(Error::from(err));
                 return circuit::Value::unknown();
             }
             Option::<C>::from(C::from_bytes(&compressed))
                 .map(circuit::Value::known)
-                .unwrap_or_else(circuit::Value::unknown)
+                .unwrap_or_else(|| {
+                    error = Some(Error::InvalidEcPointEncoding);
+                    circuit::Value::unknown()
+                })
         });
+        if let Some(error) = error {
+            return Err(error);
+        }
         let ec_point = self.loader.assign_ec_point(ec_point);
         self.common_ec_point(&ec_point)?;
         Ok(ec_point)
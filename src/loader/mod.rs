@@ -1,13 +1,21 @@
-use crate::util::{Curve, FieldOps, GroupOps, PrimeField};
-use std::{fmt::Debug, iter};
+use crate::{
+    util::{Curve, FieldOps, GroupOps, PrimeField},
+    Error,
+};
+use std::{fmt::Debug, iter, marker::PhantomData};
 
+pub mod evm;
 pub mod halo2;
+pub mod native;
+
+pub use native::NativeLoader;
 
 pub trait LoadedEcPoint<C: Curve>: Clone + Debug + GroupOps + PartialEq {
     type Loader: Loader<C, LoadedEcPoint = Self>;
 
     fn loader(&self) -> &Self::Loader;
 
+    /// Returns [`Error::EmptyMsm`] if there are no `pairs`.
     fn multi_scalar_multiplication(
         pairs: impl IntoIterator<
             Item = (
@@ -15,7 +23,7 @@ pub trait LoadedEcPoint<C: Curve>: Clone + Debug + GroupOps + PartialEq {
                 Self,
             ),
         >,
-    ) -> Self;
+    ) -> Result<Self, Error>;
 }
 
 pub trait LoadedScalar<F: PrimeField>: Clone + Debug + FieldOps {
@@ -140,7 +148,36 @@ pub trait ScalarLoader<F: PrimeField> {
 }
 
 pub trait Loader<C: Curve>: EcPointLoader<C> + ScalarLoader<C::Scalar> + Clone {
+    /// Starts metering the cost of a section named `identifier`, until the
+    /// matching [`Loader::end_cost_metering`]. Sections might be nested.
+    ///
+    /// Accumulation schemes meter `transcript_read`, `common_poly_evaluation`
+    /// and `pairing_prep` for each proof.
     fn start_cost_metering(&self, _: &str) {}
 
+    /// Ends the most recently started section.
     fn end_cost_metering(&self) {}
 }
+
+/// Section metered by [`Loader::start_cost_metering`], which is ended once
+/// dropped, so it's also ended when left early on error.
+pub struct CostMetering<'a, C: Curve, L: Loader<C>> {
+    loader: &'a L,
+    _marker: PhantomData<C>,
+}
+
+impl<'a, C: Curve, L: Loader<C>> CostMetering<'a, C, L> {
+    pub fn start(loader: &'a L, identifier: &str) -> Self {
+        loader.start_cost_metering(identifier);
+        Self {
+            loader,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, C: Curve, L: Loader<C>> Drop for CostMetering<'a, C, L> {
+    fn drop(&mut self) {
+        self.loader.end_cost_metering();
+    }
+}
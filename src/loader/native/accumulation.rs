@@ -1,8 +1,8 @@
 use crate::{
     loader::NativeLoader,
     protocol::Protocol,
-    scheme::kzg::{AccumulationStrategy, Accumulator, SameCurveAccumulation, MSM},
-    util::{fe_from_limbs, Curve, Group, PrimeCurveAffine, Transcript},
+    scheme::{AccumulationStrategy, Accumulator, SameCurveAccumulation, MSM},
+    util::{fe_from_limbs, Curve, PrimeCurveAffine, Transcript},
     Error,
 };
 use halo2_wrong::curves::{
@@ -13,8 +13,10 @@ use halo2_wrong::curves::{
 impl<C: Curve, const LIMBS: usize, const BITS: usize>
     SameCurveAccumulation<C, NativeLoader, LIMBS, BITS>
 {
-    pub fn finalize(self, g1: C) -> (C, C) {
-        self.accumulator.unwrap().evaluate(g1)
+    pub fn finalize(self, g1: C) -> Result<(C, C), Error> {
+        self.accumulator
+            .ok_or(Error::MissingAccumulator)?
+            .evaluate(g1)
     }
 }
 
@@ -26,17 +28,17 @@ impl<C: Curve, const LIMBS: usize, const BITS: usize>
         g1: M::G1Affine,
         g2: M::G2Affine,
         s_g2: M::G2Affine,
-    ) -> bool {
-        let (lhs, rhs) = self.finalize(g1.to_curve());
+    ) -> Result<bool, Error> {
+        let (lhs, rhs) = self.finalize(g1.to_curve())?;
 
         let g2 = M::G2Prepared::from(g2);
         let minus_s_g2 = M::G2Prepared::from(-s_g2);
 
         let terms = [(&lhs.into(), &g2), (&rhs.into(), &minus_s_g2)];
-        M::multi_miller_loop(&terms)
+        Ok(M::multi_miller_loop(&terms)
             .final_exponentiation()
             .is_identity()
-            .into()
+            .into())
     }
 }
 
@@ -54,43 +56,52 @@ where
         _: &NativeLoader,
         transcript: &mut T,
         statements: &[Vec<C::ScalarExt>],
-    ) -> Option<Accumulator<C, NativeLoader>> {
-        let accumulator_indices = protocol.accumulator_indices.as_ref()?;
+    ) -> Result<Option<Accumulator<C, NativeLoader>>, Error> {
+        let accumulator_indices = match protocol.accumulator_indices.as_ref() {
+            Some(accumulator_indices) => accumulator_indices,
+            None => return Ok(None),
+        };
 
         let challenges = transcript.squeeze_n_challenges(accumulator_indices.len());
         let accumulators = accumulator_indices
             .iter()
             .map(|indices| {
-                assert_eq!(indices.len(), 4 * LIMBS);
-                let [lhs_x, lhs_y, rhs_x, rhs_y]: [_; 4] = indices
-                    .chunks(4)
-                    .into_iter()
-                    .map(|indices| {
-                        fe_from_limbs::<_, _, LIMBS, BITS>(
-                            indices
-                                .iter()
-                                .map(|index| statements[index.0][index.1])
-                                .collect::<Vec<_>>()
-                                .try_into()
-                                .unwrap(),
-                        )
+                if indices.len() != 4 * LIMBS {
+                    return Err(Error::InvalidAccumulatorIndices {
+                        expected: 4 * LIMBS,
+                        actual: indices.len(),
+                    });
+                }
+                let limbs = indices
+                    .iter()
+                    .map(|&(column, row)| {
+                        statements
+                            .get(column)
+                            .and_then(|statements| statements.get(row))
+                            .copied()
+                            .ok_or(Error::MissingStatement { column, row })
                     })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let [lhs_x, lhs_y, rhs_x, rhs_y]: [_; 4] = limbs
+                    .chunks(LIMBS)
+                    .map(|limbs| fe_from_limbs::<_, _, LIMBS, BITS>(limbs.try_into().unwrap()))
                     .collect::<Vec<_>>()
                     .try_into()
                     .unwrap();
-                let lhs = <C::AffineExt as CurveAffine>::from_xy(lhs_x, lhs_y)
-                    .unwrap()
-                    .to_curve();
-                let rhs = <C::AffineExt as CurveAffine>::from_xy(rhs_x, rhs_y)
-                    .unwrap()
-                    .to_curve();
-                Accumulator::new(MSM::base(lhs), MSM::base(rhs))
+                let [lhs, rhs] = [(lhs_x, lhs_y), (rhs_x, rhs_y)].map(|(x, y)| {
+                    Option::<C::AffineExt>::from(<C::AffineExt as CurveAffine>::from_xy(x, y))
+                        .map(|ec_point| ec_point.to_curve())
+                });
+                Ok(Accumulator::new(
+                    MSM::base(lhs.ok_or(Error::InvalidAccumulator)?),
+                    MSM::base(rhs.ok_or(Error::InvalidAccumulator)?),
+                ))
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, Error>>()?;
 
-        Some(Accumulator::random_linear_combine(
-            challenges.into_iter().zip(accumulators),
-        ))
+        Ok(Some(Accumulator::random_linear_combine(
+            challenges.into_iter().map(Some).zip(accumulators),
+        )))
     }
 
     fn process(
@@ -108,4 +119,204 @@ where
         });
         Ok(proof)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        circuit::{BITS, LIMBS},
+        collect_slice,
+        loader::native::{ChallengeScalar, NativeLoader, PoseidonTranscript},
+        native::Snark,
+        protocol::Protocol,
+        scheme::{MultiOpenScheme, SameCurveAccumulation},
+        test::{
+            app_params, create_snarks, standard_plonk_snark, InstanceQuery, StandardPlonk, APP_K,
+        },
+        util::{accumulate_snark, prepare, Field, Group},
+        Error,
+    };
+    use halo2_wrong::curves::bn256::{Bn256, Fr, G1Affine, G1};
+    use halo2_wrong::halo2::poly::kzg::{
+        commitment::{KZGCommitmentScheme, ParamsKZG},
+        multiopen::ProverGWC,
+    };
+    use rand::rngs::OsRng;
+
+    fn instance_query_snark(params: &ParamsKZG<Bn256>, scheme: MultiOpenScheme) -> Snark<G1> {
+        let circuit = InstanceQuery::<Fr>::rand(OsRng);
+        let instances = circuit.instances();
+        create_snarks::<_, PoseidonTranscript<_, _>>(
+            params,
+            None,
+            vec![(circuit, instances, scheme)],
+        )
+        .pop()
+        .unwrap()
+    }
+
+    fn accumulate(
+        snark: &Snark<G1>,
+    ) -> Result<SameCurveAccumulation<G1, NativeLoader, LIMBS, BITS>, Error> {
+        let mut transcript = PoseidonTranscript::<G1Affine, _>::new(snark.proof.as_slice());
+        let mut strategy = SameCurveAccumulation::default();
+        snark.scheme.accumulate(
+            &snark.protocol,
+            &NativeLoader,
+            snark.statements.clone(),
+            &mut transcript,
+            &mut strategy,
+        )?;
+        Ok(strategy)
+    }
+
+    fn verify(params: &ParamsKZG<Bn256>, snark: &Snark<G1>) -> bool {
+        snark.verify::<Bn256, LIMBS, BITS>(params).is_ok()
+    }
+
+    #[test]
+    fn should_accept_valid_proof() {
+        let params = app_params();
+        for scheme in [MultiOpenScheme::Plonk, MultiOpenScheme::Shplonk] {
+            let snark = standard_plonk_snark::<PoseidonTranscript<_, _>>(&params, scheme);
+            assert!(verify(&params, &snark));
+        }
+    }
+
+    #[test]
+    fn should_reject_tampered_statements() {
+        let params = app_params();
+        for scheme in [MultiOpenScheme::Plonk, MultiOpenScheme::Shplonk] {
+            let mut snark = standard_plonk_snark::<PoseidonTranscript<_, _>>(&params, scheme);
+            snark.statements[0][0] += Fr::one();
+            assert!(!verify(&params, &snark));
+        }
+    }
+
+    #[test]
+    fn should_reject_tampered_proof() {
+        let params = app_params();
+        for scheme in [MultiOpenScheme::Plonk, MultiOpenScheme::Shplonk] {
+            let mut snark = standard_plonk_snark::<PoseidonTranscript<_, _>>(&params, scheme);
+            let last = snark.proof.len() - 1;
+            snark.proof[last - 32] ^= 1;
+            assert!(!verify(&params, &snark));
+        }
+    }
+
+    #[test]
+    fn should_accept_valid_proof_with_instance_queries() {
+        let params = app_params();
+        for scheme in [MultiOpenScheme::Plonk, MultiOpenScheme::Shplonk] {
+            let snark = instance_query_snark(&params, scheme);
+            assert_eq!(snark.protocol.instance_queries().len(), 2);
+            assert!(verify(&params, &snark));
+        }
+    }
+
+    #[test]
+    fn should_reject_tampered_queried_statements() {
+        let params = app_params();
+        for scheme in [MultiOpenScheme::Plonk, MultiOpenScheme::Shplonk] {
+            let mut snark = instance_query_snark(&params, scheme);
+            snark.statements[0][2] += Fr::one();
+            assert!(!verify(&params, &snark));
+        }
+    }
+
+    #[test]
+    fn should_accept_valid_proof_with_deserialized_protocol() {
+        let params = app_params();
+        let mut snark =
+            standard_plonk_snark::<PoseidonTranscript<_, _>>(&params, MultiOpenScheme::Shplonk);
+        snark.protocol = Protocol::from_bytes(&snark.protocol.to_bytes()).unwrap();
+        assert!(verify(&params, &snark));
+    }
+
+    #[test]
+    fn should_reject_mismatched_scheme() {
+        let params = app_params();
+        let mut snark =
+            standard_plonk_snark::<PoseidonTranscript<_, _>>(&params, MultiOpenScheme::Plonk);
+        snark.scheme = MultiOpenScheme::Shplonk;
+        assert!(!verify(&params, &snark));
+    }
+
+    #[test]
+    fn should_report_truncated_proof() {
+        let mut snark =
+            standard_plonk_snark::<PoseidonTranscript<_, _>>(&app_params(), MultiOpenScheme::Plonk);
+        snark.proof.truncate(snark.proof.len() - 1);
+        assert!(matches!(accumulate(&snark), Err(Error::ProofTooShort)));
+    }
+
+    #[test]
+    fn should_report_invalid_num_statements() {
+        let mut snark =
+            standard_plonk_snark::<PoseidonTranscript<_, _>>(&app_params(), MultiOpenScheme::Plonk);
+        snark.statements.push(Vec::new());
+        assert!(matches!(
+            accumulate(&snark),
+            Err(Error::InvalidNumStatements {
+                expected: 1,
+                actual: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn should_report_invalid_accumulator_indices() {
+        let mut snark =
+            standard_plonk_snark::<PoseidonTranscript<_, _>>(&app_params(), MultiOpenScheme::Plonk);
+        snark.protocol.accumulator_indices = Some(vec![vec![(0, 0)]]);
+        assert!(matches!(
+            accumulate(&snark),
+            Err(Error::InvalidAccumulatorIndices {
+                expected,
+                actual: 1
+            }) if expected == 4 * LIMBS
+        ));
+    }
+
+    #[test]
+    fn should_report_finalizing_nothing() {
+        let strategy = SameCurveAccumulation::<G1, NativeLoader, LIMBS, BITS>::default();
+        assert!(matches!(
+            strategy.finalize(G1::generator()),
+            Err(Error::MissingAccumulator)
+        ));
+    }
+
+    #[test]
+    fn should_reject_proving_with_mismatched_protocol() {
+        let circuit = StandardPlonk::<Fr>::rand(OsRng);
+        let instances = circuit.instances();
+        collect_slice!(instances);
+
+        let (params, pk, _) =
+            prepare::<KZGCommitmentScheme<Bn256>, _>(&circuit, APP_K, 1, None).unwrap();
+        let (_, _, protocol) = prepare::<KZGCommitmentScheme<Bn256>, _>(
+            &InstanceQuery::<Fr>::default(),
+            APP_K,
+            1,
+            None,
+        )
+        .unwrap();
+        let result = accumulate_snark::<
+            KZGCommitmentScheme<Bn256>,
+            ChallengeScalar<G1Affine>,
+            PoseidonTranscript<G1Affine, _>,
+            ProverGWC<_>,
+            _,
+            _,
+        >(
+            &params,
+            &pk,
+            &[circuit],
+            &[instances.as_slice()],
+            protocol,
+            OsRng,
+        );
+        assert!(matches!(result, Err(Error::ProtocolMismatch(_))));
+    }
+}
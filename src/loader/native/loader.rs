@@ -1,6 +1,7 @@
 use crate::{
     loader::{EcPointLoader, LoadedEcPoint, LoadedScalar, Loader, ScalarLoader},
     util::{Curve, FieldOps, PrimeField},
+    Error,
 };
 use lazy_static::lazy_static;
 use std::fmt::Debug;
@@ -16,12 +17,14 @@ impl<C: Curve> LoadedEcPoint<C> for C {
         &LOADER
     }
 
-    fn multi_scalar_multiplication(pairs: impl IntoIterator<Item = (C::Scalar, C)>) -> Self {
+    fn multi_scalar_multiplication(
+        pairs: impl IntoIterator<Item = (C::Scalar, C)>,
+    ) -> Result<Self, Error> {
         pairs
             .into_iter()
             .map(|(scalar, base)| base * scalar)
             .reduce(|acc, value| acc + value)
-            .unwrap()
+            .ok_or(Error::EmptyMsm)
     }
 }
 
@@ -1,4 +1,6 @@
 mod accumulation;
 mod loader;
+mod transcript;
 
-pub use loader::NativeLoader;
\ No newline at end of file
+pub use loader::NativeLoader;
+pub use transcript::{Blake2bTranscript, ChallengeScalar, Keccak256Transcript, PoseidonTranscript};
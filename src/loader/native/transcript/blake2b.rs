@@ -0,0 +1,200 @@
+use crate::{
+    loader::native::NativeLoader,
+    util::{Curve, PrimeCurveAffine, PrimeField, Transcript, TranscriptRead},
+    Error,
+};
+use blake2b_simd::{Params as Blake2bParams, State as Blake2bState};
+use halo2_wrong::curves::CurveAffine;
+use halo2_wrong::halo2::transcript::{Challenge255, EncodedChallenge};
+use std::{io::Read, marker::PhantomData};
+
+const BLAKE2B_PREFIX_CHALLENGE: u8 = 0;
+const BLAKE2B_PREFIX_POINT: u8 = 1;
+const BLAKE2B_PREFIX_SCALAR: u8 = 2;
+
+/// Native verifier counterpart of halo2's `Blake2bRead<_, _, Challenge255<_>>`.
+///
+/// It hashes with the same personalization and prefixes and derives
+/// challenges through `Challenge255`, so it reads unmodified proofs written by
+/// halo2's `Blake2bWrite`.
+pub struct Blake2bTranscript<C: CurveAffine, R> {
+    reader: R,
+    state: Blake2bState,
+    _marker: PhantomData<C>,
+}
+
+impl<C: CurveAffine, R> Blake2bTranscript<C, R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            state: Blake2bParams::new()
+                .hash_length(64)
+                .personal(b"Halo2-Transcript")
+                .to_state(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<C: CurveAffine, R: Read> Blake2bTranscript<C, R> {
+    fn read_scalar_repr(&mut self) -> Result<C::Scalar, Error> {
+        let mut data = <C::Scalar as PrimeField>::Repr::default();
+        self.reader.read_exact(data.as_mut())?;
+        Option::<C::Scalar>::from(C::Scalar::from_repr(data)).ok_or(Error::InvalidScalarEncoding)
+    }
+
+    fn read_ec_point_repr(&mut self) -> Result<C, Error> {
+        let mut compressed = C::Repr::default();
+        self.reader.read_exact(compressed.as_mut())?;
+        Option::<C>::from(C::from_bytes(&compressed)).ok_or(Error::InvalidEcPointEncoding)
+    }
+}
+
+impl<C: CurveAffine, R> Transcript<C::CurveExt, NativeLoader> for Blake2bTranscript<C, R> {
+    fn squeeze_challenge(&mut self) -> C::Scalar {
+        self.state.update(&[BLAKE2B_PREFIX_CHALLENGE]);
+        let hash: [u8; 64] = self.state.clone().finalize().as_bytes().try_into().unwrap();
+        Challenge255::<C>::new(&hash).get_scalar()
+    }
+
+    fn common_scalar(&mut self, scalar: &C::Scalar) -> Result<(), Error> {
+        self.state.update(&[BLAKE2B_PREFIX_SCALAR]);
+        self.state.update(scalar.to_repr().as_ref());
+        Ok(())
+    }
+
+    fn common_ec_point(&mut self, ec_point: &C::CurveExt) -> Result<(), Error> {
+        let coordinates = ec_point
+            .to_affine()
+            .coordinates()
+            .map(|coordinates| [*coordinates.x(), *coordinates.y()]);
+        let [x, y] = Option::<[_; 2]>::from(coordinates).ok_or(Error::InvalidEcPointEncoding)?;
+        self.state.update(&[BLAKE2B_PREFIX_POINT]);
+        self.state.update(x.to_repr().as_ref());
+        self.state.update(y.to_repr().as_ref());
+        Ok(())
+    }
+}
+
+impl<C: CurveAffine, R: Read> TranscriptRead<C::CurveExt, NativeLoader>
+    for Blake2bTranscript<C, R>
+{
+    fn read_scalar(&mut self) -> Result<C::Scalar, Error> {
+        let scalar = self.read_scalar_repr()?;
+        self.common_scalar(&scalar)?;
+        Ok(scalar)
+    }
+
+    fn read_ec_point(&mut self) -> Result<C::CurveExt, Error> {
+        let ec_point = self.read_ec_point_repr()?.to_curve();
+        self.common_ec_point(&ec_point)?;
+        Ok(ec_point)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Blake2bTranscript;
+    use crate::{
+        circuit::{BITS, LIMBS},
+        collect_slice,
+        loader::native::NativeLoader,
+        scheme::SameCurveAccumulation,
+        test::StandardPlonk,
+        util::{
+            accumulate_verified_snark, prepare, Curve, Field, Group, Transcript, TranscriptRead,
+        },
+    };
+    use halo2_wrong::curves::bn256::{Bn256, Fr, G1Affine, G1};
+    use halo2_wrong::halo2::{
+        poly::kzg::{commitment::KZGCommitmentScheme, multiopen::ProverGWC},
+        transcript::{
+            self, Blake2bRead, Blake2bWrite, Challenge255, EncodedChallenge, TranscriptReadBuffer,
+            TranscriptWrite, TranscriptWriterBuffer,
+        },
+    };
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn should_match_halo2_blake2b_transcript() {
+        let scalar = Fr::random(OsRng);
+        let ec_point = G1::random(OsRng).to_affine();
+
+        let mut writer = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(Vec::new());
+        writer.write_point(ec_point).unwrap();
+        writer.write_scalar(scalar).unwrap();
+        let proof = writer.finalize();
+
+        let mut expected = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(proof.as_slice());
+        transcript::TranscriptRead::read_point(&mut expected).unwrap();
+        transcript::TranscriptRead::read_scalar(&mut expected).unwrap();
+
+        let mut actual = Blake2bTranscript::<G1Affine, _>::new(proof.as_slice());
+        assert_eq!(
+            TranscriptRead::<G1, NativeLoader>::read_ec_point(&mut actual)
+                .unwrap()
+                .to_affine(),
+            ec_point
+        );
+        assert_eq!(
+            TranscriptRead::<G1, NativeLoader>::read_scalar(&mut actual).unwrap(),
+            scalar
+        );
+
+        for _ in 0..2 {
+            let challenge: Challenge255<_> =
+                transcript::Transcript::squeeze_challenge(&mut expected);
+            assert_eq!(
+                Transcript::<G1, NativeLoader>::squeeze_challenge(&mut actual),
+                challenge.get_scalar()
+            );
+        }
+    }
+
+    #[test]
+    fn should_accept_valid_proof_of_halo2_blake2b_transcript() {
+        let circuit = StandardPlonk::<Fr>::rand(OsRng);
+        let instances = circuit.instances();
+        collect_slice!(instances);
+
+        let (params, pk, protocol) =
+            prepare::<KZGCommitmentScheme<Bn256>, _>(&circuit, 8, 1, None).unwrap();
+        let snark = accumulate_verified_snark::<
+            KZGCommitmentScheme<Bn256>,
+            Challenge255<G1Affine>,
+            Blake2bWrite<_, _, _>,
+            Blake2bRead<_, _, _>,
+            ProverGWC<_>,
+            _,
+            _,
+        >(
+            &params,
+            &pk,
+            &[circuit],
+            &[instances.as_slice()],
+            protocol,
+            OsRng,
+        )
+        .unwrap();
+
+        let mut transcript = Blake2bTranscript::<G1Affine, _>::new(snark.proof.as_slice());
+        let mut strategy = SameCurveAccumulation::<G1, NativeLoader, LIMBS, BITS>::default();
+        snark
+            .scheme
+            .accumulate(
+                &snark.protocol,
+                &NativeLoader,
+                snark.statements.clone(),
+                &mut transcript,
+                &mut strategy,
+            )
+            .unwrap();
+        assert!(strategy
+            .decide::<Bn256>(params.get_g()[0], params.g2(), params.s_g2())
+            .unwrap());
+    }
+}
@@ -0,0 +1,323 @@
+use crate::{
+    loader::native::{ChallengeScalar, NativeLoader},
+    util::{
+        big_to_fe, modulus, Curve, Field, PrimeCurveAffine, PrimeField, Transcript, TranscriptRead,
+    },
+    Error,
+};
+use halo2_wrong::curves::CurveAffine;
+use halo2_wrong::halo2::transcript::{
+    self, EncodedChallenge, TranscriptReadBuffer, TranscriptWrite, TranscriptWriterBuffer,
+};
+use num_bigint::BigUint;
+use sha3::{Digest, Keccak256};
+use std::{
+    io::{self, Read, Write},
+    marker::PhantomData,
+};
+
+const KECCAK256_PREFIX_CHALLENGE: u8 = 0;
+const KECCAK256_PREFIX_POINT: u8 = 1;
+const KECCAK256_PREFIX_SCALAR: u8 = 2;
+
+/// Keccak256 transcript meant for proofs verified on EVM.
+///
+/// Challenges are derived like halo2's `Blake2bWrite`, by hashing everything
+/// absorbed so far with a prefix byte per item, but the 32 bytes digest is
+/// read as a big-endian integer and reduced into the scalar field. Proof
+/// elements are encoded as the EVM expects them, scalars as 32 bytes
+/// big-endian and points as big-endian `x` followed by `y`, with the identity
+/// as `(0, 0)`, so they can be passed to the bn256 precompiles as is.
+pub struct Keccak256Transcript<C: CurveAffine, S> {
+    stream: S,
+    state: Keccak256,
+    _marker: PhantomData<C>,
+}
+
+impl<C: CurveAffine, S> Keccak256Transcript<C, S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            state: Keccak256::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    fn encode_scalar(scalar: &C::Scalar) -> Vec<u8> {
+        fe_to_be_bytes(scalar)
+    }
+
+    fn encode_ec_point(ec_point: &C) -> Vec<u8> {
+        let coordinates = Option::from(
+            ec_point
+                .coordinates()
+                .map(|coordinates| [*coordinates.x(), *coordinates.y()]),
+        )
+        .unwrap_or([C::Base::zero(); 2]);
+        coordinates.iter().flat_map(fe_to_be_bytes).collect()
+    }
+
+    fn squeeze(&mut self) -> C::Scalar {
+        self.state.update([KECCAK256_PREFIX_CHALLENGE]);
+        let hash = self.state.clone().finalize();
+        big_to_fe(BigUint::from_bytes_be(hash.as_slice()) % modulus::<C::Scalar>())
+    }
+
+    fn absorb_scalar(&mut self, scalar: &C::Scalar) {
+        self.state.update([KECCAK256_PREFIX_SCALAR]);
+        self.state.update(Self::encode_scalar(scalar));
+    }
+
+    fn absorb_ec_point(&mut self, ec_point: &C) {
+        self.state.update([KECCAK256_PREFIX_POINT]);
+        self.state.update(Self::encode_ec_point(ec_point));
+    }
+}
+
+impl<C: CurveAffine, R: Read> Keccak256Transcript<C, R> {
+    fn read_fe<F: PrimeField>(&mut self) -> Result<Option<F>, Error> {
+        let mut data = F::Repr::default();
+        self.stream.read_exact(data.as_mut())?;
+        Ok(fe_from_be_bytes(data))
+    }
+
+    fn read_scalar_repr(&mut self) -> Result<C::Scalar, Error> {
+        self.read_fe()?.ok_or(Error::InvalidScalarEncoding)
+    }
+
+    fn read_ec_point_repr(&mut self) -> Result<C, Error> {
+        let x = self.read_fe::<C::Base>()?;
+        let y = self.read_fe::<C::Base>()?;
+        match (x, y) {
+            (Some(x), Some(y)) if bool::from(x.is_zero() & y.is_zero()) => Ok(C::identity()),
+            (Some(x), Some(y)) => {
+                Option::from(C::from_xy(x, y)).ok_or(Error::InvalidEcPointEncoding)
+            }
+            _ => Err(Error::InvalidEcPointEncoding),
+        }
+    }
+}
+
+impl<C: CurveAffine, S> Transcript<C::CurveExt, NativeLoader> for Keccak256Transcript<C, S> {
+    fn squeeze_challenge(&mut self) -> C::Scalar {
+        self.squeeze()
+    }
+
+    fn common_scalar(&mut self, scalar: &C::Scalar) -> Result<(), Error> {
+        self.absorb_scalar(scalar);
+        Ok(())
+    }
+
+    fn common_ec_point(&mut self, ec_point: &C::CurveExt) -> Result<(), Error> {
+        self.absorb_ec_point(&ec_point.to_affine());
+        Ok(())
+    }
+}
+
+impl<C: CurveAffine, R: Read> TranscriptRead<C::CurveExt, NativeLoader>
+    for Keccak256Transcript<C, R>
+{
+    fn read_scalar(&mut self) -> Result<C::Scalar, Error> {
+        let scalar = self.read_scalar_repr()?;
+        self.absorb_scalar(&scalar);
+        Ok(scalar)
+    }
+
+    fn read_ec_point(&mut self) -> Result<C::CurveExt, Error> {
+        let ec_point = self.read_ec_point_repr()?;
+        self.absorb_ec_point(&ec_point);
+        Ok(ec_point.to_curve())
+    }
+}
+
+impl<C: CurveAffine, S> transcript::Transcript<C, ChallengeScalar<C>>
+    for Keccak256Transcript<C, S>
+{
+    fn squeeze_challenge(&mut self) -> ChallengeScalar<C> {
+        ChallengeScalar::new(&self.squeeze())
+    }
+
+    fn common_point(&mut self, ec_point: C) -> io::Result<()> {
+        self.absorb_ec_point(&ec_point);
+        Ok(())
+    }
+
+    fn common_scalar(&mut self, scalar: C::Scalar) -> io::Result<()> {
+        self.absorb_scalar(&scalar);
+        Ok(())
+    }
+}
+
+impl<C: CurveAffine, R: Read> transcript::TranscriptRead<C, ChallengeScalar<C>>
+    for Keccak256Transcript<C, R>
+{
+    fn read_point(&mut self) -> io::Result<C> {
+        let ec_point = self.read_ec_point_repr()?;
+        self.absorb_ec_point(&ec_point);
+        Ok(ec_point)
+    }
+
+    fn read_scalar(&mut self) -> io::Result<C::Scalar> {
+        let scalar = self.read_scalar_repr()?;
+        self.absorb_scalar(&scalar);
+        Ok(scalar)
+    }
+}
+
+impl<C: CurveAffine, R: Read> TranscriptReadBuffer<R, C, ChallengeScalar<C>>
+    for Keccak256Transcript<C, R>
+{
+    fn init(reader: R) -> Self {
+        Self::new(reader)
+    }
+}
+
+impl<C: CurveAffine, W: Write> TranscriptWrite<C, ChallengeScalar<C>>
+    for Keccak256Transcript<C, W>
+{
+    fn write_point(&mut self, ec_point: C) -> io::Result<()> {
+        self.absorb_ec_point(&ec_point);
+        self.stream.write_all(&Self::encode_ec_point(&ec_point))
+    }
+
+    fn write_scalar(&mut self, scalar: C::Scalar) -> io::Result<()> {
+        self.absorb_scalar(&scalar);
+        self.stream.write_all(&Self::encode_scalar(&scalar))
+    }
+}
+
+impl<C: CurveAffine, W: Write> TranscriptWriterBuffer<W, C, ChallengeScalar<C>>
+    for Keccak256Transcript<C, W>
+{
+    fn init(writer: W) -> Self {
+        Self::new(writer)
+    }
+
+    fn finalize(self) -> W {
+        self.stream
+    }
+}
+
+fn fe_to_be_bytes<F: PrimeField>(fe: &F) -> Vec<u8> {
+    let mut bytes = fe.to_repr().as_ref().to_vec();
+    bytes.reverse();
+    bytes
+}
+
+fn fe_from_be_bytes<F: PrimeField>(mut repr: F::Repr) -> Option<F> {
+    repr.as_mut().reverse();
+    Option::from(F::from_repr(repr))
+}
+
+#[cfg(test)]
+mod test {
+    use super::Keccak256Transcript;
+    use crate::{
+        circuit::{BITS, LIMBS},
+        collect_slice,
+        loader::native::{ChallengeScalar, NativeLoader},
+        scheme::SameCurveAccumulation,
+        test::StandardPlonk,
+        util::{
+            accumulate_verified_snark, prepare, Curve, Field, Group, PrimeCurveAffine, Transcript,
+            TranscriptRead,
+        },
+        Error,
+    };
+    use halo2_wrong::curves::bn256::{Bn256, Fr, G1Affine, G1};
+    use halo2_wrong::halo2::{
+        poly::kzg::{commitment::KZGCommitmentScheme, multiopen::ProverSHPLONK},
+        transcript::{self, EncodedChallenge, TranscriptWrite, TranscriptWriterBuffer},
+    };
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn should_read_what_is_written() {
+        let scalar = Fr::random(OsRng);
+        let ec_points = [G1::random(OsRng).to_affine(), G1Affine::identity()];
+
+        let mut writer = Keccak256Transcript::<G1Affine, _>::init(Vec::new());
+        writer.write_scalar(scalar).unwrap();
+        for ec_point in ec_points {
+            writer.write_point(ec_point).unwrap();
+        }
+        let challenge: ChallengeScalar<_> = transcript::Transcript::squeeze_challenge(&mut writer);
+        let proof = writer.finalize();
+        assert_eq!(proof.len(), 32 + 2 * 64);
+
+        let mut reader = Keccak256Transcript::<G1Affine, _>::new(proof.as_slice());
+        assert_eq!(
+            TranscriptRead::<G1, NativeLoader>::read_scalar(&mut reader).unwrap(),
+            scalar
+        );
+        for ec_point in ec_points {
+            assert_eq!(
+                TranscriptRead::<G1, NativeLoader>::read_ec_point(&mut reader)
+                    .unwrap()
+                    .to_affine(),
+                ec_point
+            );
+        }
+        assert_eq!(
+            Transcript::<G1, NativeLoader>::squeeze_challenge(&mut reader),
+            challenge.get_scalar()
+        );
+    }
+
+    #[test]
+    fn should_reject_off_curve_point() {
+        let proof = [vec![0; 31], vec![1], vec![0; 31], vec![3]].concat();
+        let mut reader = Keccak256Transcript::<G1Affine, _>::new(proof.as_slice());
+        assert!(matches!(
+            TranscriptRead::<G1, NativeLoader>::read_ec_point(&mut reader),
+            Err(Error::InvalidEcPointEncoding)
+        ));
+    }
+
+    #[test]
+    fn should_accept_valid_proof_with_keccak256_transcript() {
+        let circuit = StandardPlonk::<Fr>::rand(OsRng);
+        let instances = circuit.instances();
+        collect_slice!(instances);
+
+        let (params, pk, protocol) =
+            prepare::<KZGCommitmentScheme<Bn256>, _>(&circuit, 8, 1, None).unwrap();
+        let snark = accumulate_verified_snark::<
+            KZGCommitmentScheme<Bn256>,
+            ChallengeScalar<G1Affine>,
+            Keccak256Transcript<G1Affine, _>,
+            Keccak256Transcript<G1Affine, _>,
+            ProverSHPLONK<_>,
+            _,
+            _,
+        >(
+            &params,
+            &pk,
+            &[circuit],
+            &[instances.as_slice()],
+            protocol,
+            OsRng,
+        )
+        .unwrap();
+
+        let mut transcript = Keccak256Transcript::<G1Affine, _>::new(snark.proof.as_slice());
+        let mut strategy = SameCurveAccumulation::<G1, NativeLoader, LIMBS, BITS>::default();
+        snark
+            .scheme
+            .accumulate(
+                &snark.protocol,
+                &NativeLoader,
+                snark.statements.clone(),
+                &mut transcript,
+                &mut strategy,
+            )
+            .unwrap();
+        assert!(strategy
+            .decide::<Bn256>(params.get_g()[0], params.g2(), params.s_g2())
+            .unwrap());
+    }
+}
@@ -0,0 +1,24 @@
+use halo2_wrong::curves::CurveAffine;
+use halo2_wrong::halo2::transcript::EncodedChallenge;
+
+mod blake2b;
+mod keccak;
+mod poseidon;
+
+pub use self::blake2b::Blake2bTranscript;
+pub use self::keccak::Keccak256Transcript;
+pub use self::poseidon::PoseidonTranscript;
+
+pub struct ChallengeScalar<C: CurveAffine>(C::Scalar);
+
+impl<C: CurveAffine> EncodedChallenge<C> for ChallengeScalar<C> {
+    type Input = C::Scalar;
+
+    fn new(challenge_input: &C::Scalar) -> Self {
+        ChallengeScalar(*challenge_input)
+    }
+
+    fn get_scalar(&self) -> C::Scalar {
+        self.0
+    }
+}
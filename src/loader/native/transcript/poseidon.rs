@@ -0,0 +1,169 @@
+use crate::{
+    loader::{
+        halo2::{RATE, R_F, R_P, T},
+        native::{ChallengeScalar, NativeLoader},
+    },
+    util::{fe_to_fe, Curve, PrimeCurveAffine, PrimeField, Transcript, TranscriptRead},
+    Error,
+};
+use halo2_wrong::curves::CurveAffine;
+use halo2_wrong::halo2::transcript::{
+    self, EncodedChallenge, TranscriptReadBuffer, TranscriptWrite, TranscriptWriterBuffer,
+};
+use poseidon::Poseidon;
+use std::io::{self, Read, Write};
+
+/// Native counterpart of [`crate::loader::halo2::PoseidonTranscript`].
+///
+/// It uses the same Poseidon spec and absorbs points with `NativeRepresentation`
+/// (`x` and `y` reduced into the scalar field), so the challenges squeezed here
+/// are identical to the ones computed in circuit. Besides the verifier side it
+/// implements halo2's `TranscriptWrite` to produce proofs the circuit can read.
+pub struct PoseidonTranscript<C: CurveAffine, S> {
+    stream: S,
+    buf: Poseidon<C::Scalar, T, RATE>,
+}
+
+impl<C: CurveAffine, S> PoseidonTranscript<C, S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            buf: Poseidon::new(R_F, R_P),
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    fn encode_ec_point(ec_point: &C) -> Option<[C::Scalar; 2]> {
+        Option::from(
+            ec_point
+                .coordinates()
+                .map(|coordinates| [*coordinates.x(), *coordinates.y()].map(fe_to_fe)),
+        )
+    }
+
+    fn absorb_scalar(&mut self, scalar: &C::Scalar) {
+        self.buf.update(&[*scalar]);
+    }
+
+    fn absorb_ec_point(&mut self, ec_point: &C) -> io::Result<()> {
+        let encoded = Self::encode_ec_point(ec_point).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                "Cannot write point at infinity to the transcript",
+            )
+        })?;
+        self.buf.update(&encoded);
+        Ok(())
+    }
+}
+
+impl<C: CurveAffine, R: Read> PoseidonTranscript<C, R> {
+    fn read_scalar_repr(&mut self) -> Result<C::Scalar, Error> {
+        let mut data = <C::Scalar as PrimeField>::Repr::default();
+        self.stream.read_exact(data.as_mut())?;
+        Option::<C::Scalar>::from(C::Scalar::from_repr(data)).ok_or(Error::InvalidScalarEncoding)
+    }
+
+    fn read_ec_point_repr(&mut self) -> Result<C, Error> {
+        let mut compressed = C::Repr::default();
+        self.stream.read_exact(compressed.as_mut())?;
+        Option::<C>::from(C::from_bytes(&compressed)).ok_or(Error::InvalidEcPointEncoding)
+    }
+}
+
+impl<C: CurveAffine, S> Transcript<C::CurveExt, NativeLoader> for PoseidonTranscript<C, S> {
+    fn squeeze_challenge(&mut self) -> C::Scalar {
+        self.buf.squeeze()
+    }
+
+    fn common_scalar(&mut self, scalar: &C::Scalar) -> Result<(), Error> {
+        self.absorb_scalar(scalar);
+        Ok(())
+    }
+
+    fn common_ec_point(&mut self, ec_point: &C::CurveExt) -> Result<(), Error> {
+        Ok(self.absorb_ec_point(&ec_point.to_affine())?)
+    }
+}
+
+impl<C: CurveAffine, R: Read> TranscriptRead<C::CurveExt, NativeLoader>
+    for PoseidonTranscript<C, R>
+{
+    fn read_scalar(&mut self) -> Result<C::Scalar, Error> {
+        let scalar = self.read_scalar_repr()?;
+        Transcript::<C::CurveExt, NativeLoader>::common_scalar(self, &scalar)?;
+        Ok(scalar)
+    }
+
+    fn read_ec_point(&mut self) -> Result<C::CurveExt, Error> {
+        let ec_point = self.read_ec_point_repr()?.to_curve();
+        Transcript::<C::CurveExt, NativeLoader>::common_ec_point(self, &ec_point)?;
+        Ok(ec_point)
+    }
+}
+
+impl<C: CurveAffine, S> transcript::Transcript<C, ChallengeScalar<C>> for PoseidonTranscript<C, S> {
+    fn squeeze_challenge(&mut self) -> ChallengeScalar<C> {
+        ChallengeScalar::new(&self.buf.squeeze())
+    }
+
+    fn common_point(&mut self, ec_point: C) -> io::Result<()> {
+        self.absorb_ec_point(&ec_point)
+    }
+
+    fn common_scalar(&mut self, scalar: C::Scalar) -> io::Result<()> {
+        self.absorb_scalar(&scalar);
+        Ok(())
+    }
+}
+
+impl<C: CurveAffine, R: Read> transcript::TranscriptRead<C, ChallengeScalar<C>>
+    for PoseidonTranscript<C, R>
+{
+    fn read_point(&mut self) -> io::Result<C> {
+        let ec_point = self.read_ec_point_repr()?;
+        self.absorb_ec_point(&ec_point)?;
+        Ok(ec_point)
+    }
+
+    fn read_scalar(&mut self) -> io::Result<C::Scalar> {
+        let scalar = self.read_scalar_repr()?;
+        self.absorb_scalar(&scalar);
+        Ok(scalar)
+    }
+}
+
+impl<C: CurveAffine, R: Read> TranscriptReadBuffer<R, C, ChallengeScalar<C>>
+    for PoseidonTranscript<C, R>
+{
+    fn init(reader: R) -> Self {
+        Self::new(reader)
+    }
+}
+
+impl<C: CurveAffine, W: Write> TranscriptWrite<C, ChallengeScalar<C>> for PoseidonTranscript<C, W> {
+    fn write_point(&mut self, ec_point: C) -> io::Result<()> {
+        self.absorb_ec_point(&ec_point)?;
+        self.stream.write_all(ec_point.to_bytes().as_ref())
+    }
+
+    fn write_scalar(&mut self, scalar: C::Scalar) -> io::Result<()> {
+        self.absorb_scalar(&scalar);
+        self.stream.write_all(scalar.to_repr().as_ref())
+    }
+}
+
+impl<C: CurveAffine, W: Write> TranscriptWriterBuffer<W, C, ChallengeScalar<C>>
+    for PoseidonTranscript<C, W>
+{
+    fn init(writer: W) -> Self {
+        Self::new(writer)
+    }
+
+    fn finalize(self) -> W {
+        self.stream
+    }
+}
@@ -1,8 +1,42 @@
-use crate::util::{Curve, Group};
+use crate::loader::native::{NativeLoader, PoseidonTranscript};
 use crate::protocol::Protocol;
+use crate::scheme::{MultiOpenScheme, SameCurveAccumulation};
+use crate::util::{
+    serialization::{
+        invalid_data, read_scalar, read_u8, read_usize, read_vec, write_scalar, write_usize,
+        write_vec,
+    },
+    Curve, Group, GroupEncoding, TranscriptRead,
+};
+use crate::Error;
+use halo2_wrong::curves::{pairing::MultiMillerLoop, CurveExt};
+use halo2_wrong::halo2::poly::kzg::commitment::ParamsKZG;
+use std::fmt::Debug;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{self, Read, Write},
+};
 
+const MAGIC: &[u8; 4] = b"SNRK";
+const VERSION: u8 = 1;
+
+/// Proof together with the protocol and statements it's verified against.
+///
+/// It's encoded by [`Snark::write`] as follows, with lengths as little-endian
+/// `u32`:
+///
+/// ```text
+/// magic           | "SNRK"
+/// version         | u8
+/// protocol        | u8 tag 0 followed by the `Protocol::write` encoding, or
+///                 | u8 tag 1 followed by the 32 bytes `Protocol::digest`
+/// scheme          | u8, 0 for Plonk and 1 for Shplonk
+/// statements      | column count, then per column its length and scalars
+/// proof           | length, then bytes
+/// ```
 pub struct Snark<C: Curve> {
     pub(crate) protocol: Protocol<C>,
+    pub(crate) scheme: MultiOpenScheme,
     pub(crate) statements: Vec<Vec<<C as Group>::Scalar>>,
     pub(crate) proof: Vec<u8>,
 }
@@ -10,13 +44,381 @@ pub struct Snark<C: Curve> {
 impl<C: Curve> Snark<C> {
     pub fn new(
         protocol: Protocol<C>,
+        scheme: MultiOpenScheme,
         statements: Vec<Vec<<C as Group>::Scalar>>,
         proof: Vec<u8>,
     ) -> Self {
         Snark {
             protocol,
+            scheme,
             statements,
             proof,
         }
     }
-}
\ No newline at end of file
+
+    pub fn protocol(&self) -> &Protocol<C> {
+        &self.protocol
+    }
+
+    pub fn scheme(&self) -> MultiOpenScheme {
+        self.scheme
+    }
+
+    pub fn statements(&self) -> &[Vec<<C as Group>::Scalar>] {
+        &self.statements
+    }
+
+    pub fn proof(&self) -> &[u8] {
+        &self.proof
+    }
+}
+
+impl<C: CurveExt> Snark<C> {
+    /// Verifies the snark against `params`, reading the proof with
+    /// [`PoseidonTranscript`].
+    ///
+    /// `LIMBS` and `BITS` are the limb layout of accumulators the protocol
+    /// might take as statements, and are irrelevant otherwise.
+    pub fn verify<E, const LIMBS: usize, const BITS: usize>(
+        &self,
+        params: &ParamsKZG<E>,
+    ) -> Result<(), Error>
+    where
+        E: MultiMillerLoop<G1 = C, G1Affine = C::AffineExt> + Debug,
+    {
+        let mut transcript = PoseidonTranscript::<C::AffineExt, _>::new(self.proof.as_slice());
+        self.verify_with_transcript::<E, _, LIMBS, BITS>(params, &mut transcript)
+    }
+
+    /// Like [`Snark::verify`], but reads the proof from `transcript`, which
+    /// must be the kind the proof was created with.
+    ///
+    /// Fails with the error of the stage that went wrong, e.g.
+    /// [`Error::ProofTooShort`] while reading the proof, or
+    /// [`Error::PairingCheckFailed`] if the proof is well-formed but invalid.
+    pub fn verify_with_transcript<E, T, const LIMBS: usize, const BITS: usize>(
+        &self,
+        params: &ParamsKZG<E>,
+        transcript: &mut T,
+    ) -> Result<(), Error>
+    where
+        E: MultiMillerLoop<G1 = C, G1Affine = C::AffineExt> + Debug,
+        T: TranscriptRead<C, NativeLoader>,
+    {
+        let mut strategy = SameCurveAccumulation::<_, _, LIMBS, BITS>::default();
+        self.scheme.accumulate(
+            &self.protocol,
+            &NativeLoader,
+            self.statements.clone(),
+            transcript,
+            &mut strategy,
+        )?;
+
+        if strategy.decide::<E>(params.get_g()[0], params.g2(), params.s_g2())? {
+            Ok(())
+        } else {
+            Err(Error::PairingCheckFailed)
+        }
+    }
+}
+
+impl<C: Curve + GroupEncoding> Snark<C> {
+    /// Writes the snark with its protocol embedded.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.write_header(writer)?;
+        writer.write_all(&[0])?;
+        self.protocol.write(writer)?;
+        self.write_body(writer)
+    }
+
+    /// Writes the snark referencing its protocol by [`Protocol::digest`], for
+    /// receivers that already hold the protocol.
+    pub fn write_with_digest<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.write_header(writer)?;
+        writer.write_all(&[1])?;
+        writer.write_all(&self.protocol.digest())?;
+        self.write_body(writer)
+    }
+
+    /// Reads a snark written by [`Snark::write`].
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Self::read_with_protocol(reader, |_| None)
+    }
+
+    /// Reads a snark written by [`Snark::write`] or [`Snark::write_with_digest`],
+    /// resolving a referenced protocol by its digest with `resolve`.
+    pub fn read_with_protocol<R: Read>(
+        reader: &mut R,
+        resolve: impl FnOnce(&[u8; 32]) -> Option<Protocol<C>>,
+    ) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("Invalid snark magic"));
+        }
+        let version = read_u8(reader)?;
+        if version != VERSION {
+            return Err(invalid_data(format!(
+                "Unsupported snark version {}",
+                version
+            )));
+        }
+
+        let protocol = match read_u8(reader)? {
+            0 => Protocol::read(reader)?,
+            1 => {
+                let mut digest = [0; 32];
+                reader.read_exact(&mut digest)?;
+                resolve(&digest)
+                    .filter(|protocol| protocol.digest() == digest)
+                    .ok_or_else(|| invalid_data("Unknown protocol digest"))?
+            }
+            _ => return Err(invalid_data("Invalid protocol tag")),
+        };
+        let scheme = match read_u8(reader)? {
+            0 => MultiOpenScheme::Plonk,
+            1 => MultiOpenScheme::Shplonk,
+            _ => return Err(invalid_data("Invalid multi-open scheme")),
+        };
+        let statements = read_vec(reader, |reader| read_vec(reader, read_scalar))?;
+        let proof = {
+            let len = read_usize(reader)?;
+            let mut proof = Vec::new();
+            reader.by_ref().take(len as u64).read_to_end(&mut proof)?;
+            if proof.len() != len {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            proof
+        };
+
+        Ok(Self::new(protocol, scheme, statements, proof))
+    }
+
+    /// Encodes the snark by [`Snark::write`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// Reads a snark with embedded protocol from `bytes`, rejecting any
+    /// trailing data.
+    pub fn from_bytes(mut bytes: &[u8]) -> io::Result<Self> {
+        let snark = Self::read(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(invalid_data("Trailing bytes after snark"));
+        }
+        Ok(snark)
+    }
+
+    fn write_header<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])
+    }
+
+    fn write_body<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&[match self.scheme {
+            MultiOpenScheme::Plonk => 0,
+            MultiOpenScheme::Shplonk => 1,
+        }])?;
+        write_vec(writer, &self.statements, |writer, statements| {
+            write_vec(writer, statements, write_scalar)
+        })?;
+        write_usize(writer, self.proof.len())?;
+        writer.write_all(&self.proof)
+    }
+}
+
+/// Groups `snarks` by [`Protocol::digest`], keeping their relative order, so
+/// snarks of the same circuit can be aggregated together.
+pub fn group_by_protocol<C: Curve + GroupEncoding>(
+    snarks: impl IntoIterator<Item = Snark<C>>,
+) -> BTreeMap<[u8; 32], Vec<Snark<C>>> {
+    snarks
+        .into_iter()
+        .fold(BTreeMap::new(), |mut groups, snark| {
+            groups
+                .entry(snark.protocol.digest())
+                .or_insert_with(Vec::new)
+                .push(snark);
+            groups
+        })
+}
+
+/// Set of [`Protocol::digest`]s an aggregator accepts snarks of.
+#[derive(Clone, Debug, Default)]
+pub struct ProtocolWhitelist {
+    digests: BTreeSet<[u8; 32]>,
+}
+
+impl ProtocolWhitelist {
+    pub fn new<'a, C: Curve + GroupEncoding + 'a>(
+        protocols: impl IntoIterator<Item = &'a Protocol<C>>,
+    ) -> Self {
+        Self {
+            digests: protocols.into_iter().map(Protocol::digest).collect(),
+        }
+    }
+
+    pub fn insert_digest(&mut self, digest: [u8; 32]) {
+        self.digests.insert(digest);
+    }
+
+    pub fn contains_digest(&self, digest: &[u8; 32]) -> bool {
+        self.digests.contains(digest)
+    }
+
+    pub fn contains<C: Curve + GroupEncoding>(&self, protocol: &Protocol<C>) -> bool {
+        self.contains_digest(&protocol.digest())
+    }
+
+    /// Returns [`Error::UnknownProtocol`] if the snark's protocol isn't
+    /// whitelisted.
+    pub fn check<C: Curve + GroupEncoding>(&self, snark: &Snark<C>) -> Result<(), Error> {
+        let digest = snark.protocol.digest();
+        if self.contains_digest(&digest) {
+            Ok(())
+        } else {
+            Err(Error::UnknownProtocol(digest))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{group_by_protocol, ProtocolWhitelist, Snark};
+    use crate::{
+        circuit::{BITS, LIMBS},
+        loader::native::PoseidonTranscript,
+        scheme::MultiOpenScheme,
+        test::{app_params, app_snark, standard_plonk_snark, standard_plonk_snarks},
+        util::{Expression, Field, Query},
+        Error,
+    };
+    use halo2_wrong::curves::bn256::{Bn256, Fr, G1};
+    use std::collections::BTreeSet;
+
+    /// Proves a `StandardPlonk` under params of its own.
+    fn snark() -> Snark<G1> {
+        app_snark(&app_params())
+    }
+
+    fn assert_eq_snark(lhs: &Snark<G1>, rhs: &Snark<G1>) {
+        assert_eq!(lhs.protocol().to_bytes(), rhs.protocol().to_bytes());
+        assert_eq!(lhs.scheme(), rhs.scheme());
+        assert_eq!(lhs.statements(), rhs.statements());
+        assert_eq!(lhs.proof(), rhs.proof());
+    }
+
+    #[test]
+    fn should_round_trip_with_embedded_protocol() {
+        let snark = snark();
+        let decoded = Snark::<G1>::from_bytes(&snark.to_bytes()).unwrap();
+        assert_eq_snark(&decoded, &snark);
+    }
+
+    #[test]
+    fn should_round_trip_with_protocol_digest() {
+        let snark = snark();
+        let mut bytes = Vec::new();
+        snark.write_with_digest(&mut bytes).unwrap();
+        assert!(bytes.len() < snark.to_bytes().len());
+
+        let decoded = Snark::<G1>::read_with_protocol(&mut bytes.as_slice(), |digest| {
+            (*digest == snark.protocol().digest()).then(|| snark.protocol().clone())
+        })
+        .unwrap();
+        assert_eq_snark(&decoded, &snark);
+
+        assert!(Snark::<G1>::read(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn should_group_and_whitelist_by_protocol() {
+        let params = app_params();
+        let mut snarks = standard_plonk_snarks::<PoseidonTranscript<_, _>>(
+            &params,
+            &[MultiOpenScheme::Plonk; 2],
+        );
+        let (b, a) = (snarks.pop().unwrap(), snarks.pop().unwrap());
+        let other = {
+            let mut snark =
+                standard_plonk_snark::<PoseidonTranscript<_, _>>(&params, MultiOpenScheme::Plonk);
+            snark.protocol.transcript_initial_state += Fr::one();
+            snark
+        };
+        // Same circuit, but preprocessed commitments under other params
+        let other_params = snark();
+        let [a_digest, b_digest, other_digest, other_params_digest] =
+            [&a, &b, &other, &other_params].map(|snark| snark.protocol().digest());
+        assert_eq!(a_digest, b_digest);
+        assert_ne!(a_digest, other_digest);
+        assert_ne!(a_digest, other_params_digest);
+
+        let whitelist = ProtocolWhitelist::new([a.protocol()]);
+        assert!(whitelist.check(&b).is_ok());
+        assert!(matches!(
+            whitelist.check(&other),
+            Err(Error::UnknownProtocol(digest)) if digest == other_digest
+        ));
+
+        let groups = group_by_protocol([a, other, b, other_params]);
+        assert_eq!(groups.values().map(Vec::len).sum::<usize>(), 4);
+        assert_eq!(
+            groups.values().map(Vec::len).collect::<BTreeSet<_>>(),
+            BTreeSet::from([1, 2])
+        );
+        assert_eq!(groups.len(), 3);
+    }
+
+    #[test]
+    fn should_group_and_whitelist_deeply_nested_protocol() {
+        let shallow = snark();
+        let mut deep = snark();
+        // Nested like the constraints of all gates folded by a challenge
+        let leaf = || Expression::Polynomial(Query::new(0, 0));
+        deep.protocol.relations = vec![(0..1000).fold(leaf(), |acc, _| acc + leaf())];
+
+        let whitelist = ProtocolWhitelist::new([deep.protocol()]);
+        assert!(whitelist.check(&deep).is_ok());
+        assert!(whitelist.check(&shallow).is_err());
+
+        let digest = deep.protocol().digest();
+        let groups = group_by_protocol([shallow, deep]);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[&digest].len(), 1);
+    }
+
+    #[test]
+    fn should_reject_truncated_snark() {
+        let bytes = snark().to_bytes();
+        assert!(Snark::<G1>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn should_report_failed_verification_stage() {
+        let params = app_params();
+        let snark =
+            standard_plonk_snark::<PoseidonTranscript<_, _>>(&params, MultiOpenScheme::Shplonk);
+        assert!(snark.verify::<Bn256, LIMBS, BITS>(&params).is_ok());
+
+        let mut tampered = Snark::new(
+            snark.protocol.clone(),
+            snark.scheme,
+            snark.statements.clone(),
+            snark.proof.clone(),
+        );
+        tampered.statements[0][0] += Fr::one();
+        assert!(matches!(
+            tampered.verify::<Bn256, LIMBS, BITS>(&params),
+            Err(Error::PairingCheckFailed)
+        ));
+
+        let mut truncated = snark;
+        truncated.proof.truncate(truncated.proof.len() / 2);
+        assert!(matches!(
+            truncated.verify::<Bn256, LIMBS, BITS>(&params),
+            Err(Error::ProofTooShort)
+        ));
+    }
+}
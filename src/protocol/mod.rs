@@ -1,5 +1,6 @@
 use crate::{
     util::{CommonPolynomial, Domain, Expression, Query, Rotation, Curve},
+    Error,
 };
 use halo2_wrong::{
 	halo2::{
@@ -9,7 +10,11 @@ use halo2_wrong::{
 		transcript::{EncodedChallenge, Transcript},
 	},
 };
-use std::{io, iter};
+use std::{collections::BTreeSet, io, iter};
+
+mod serialization;
+
+pub use serialization::digest_protocols;
 
 #[derive(Clone, Debug)]
 pub struct Protocol<C: Curve> {
@@ -31,6 +36,17 @@ impl<C: Curve> Protocol<C> {
     }
 
     pub fn langranges<T>(&self, statements: &[Vec<T>]) -> impl IntoIterator<Item = i32> {
+        let max_statement_len = statements
+            .iter()
+            .map(|statement| statement.len())
+            .max()
+            .unwrap_or_default() as i32;
+        let rotations = self
+            .instance_queries()
+            .into_iter()
+            .map(|query| query.rotation.0)
+            .collect::<BTreeSet<_>>();
+
         self.relations
             .iter()
             .cloned()
@@ -38,12 +54,25 @@ impl<C: Curve> Protocol<C> {
             .used_langrange()
             .into_iter()
             .chain(
-                0..statements
-                    .iter()
-                    .map(|statement| statement.len())
-                    .max()
-                    .unwrap_or_default() as i32,
+                rotations
+                    .into_iter()
+                    .flat_map(move |rotation| -rotation..max_statement_len - rotation),
             )
+            .collect::<BTreeSet<_>>()
+    }
+
+    /// Instance queries made by `relations`. Like halo2's KZG verifier, they
+    /// are never opened but evaluated from the statements, where a query with
+    /// rotation `r` evaluates to `Σ_j statement_j·L_{j-r}(z)`.
+    pub fn instance_queries(&self) -> Vec<Query> {
+        let instances = self.preprocessed.len()..self.preprocessed.len() + self.num_statement;
+        self.relations
+            .iter()
+            .flat_map(Expression::used_query)
+            .filter(|query| instances.contains(&query.poly))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
     }
 }
 
@@ -59,21 +88,76 @@ struct Polynomials<'a, F: FieldExt> {
     num_fixed: usize,
     num_permutation_fixed: usize,
     num_instance: usize,
-    num_advice: usize,
+    num_advice: Vec<usize>,
+    num_challenge: Vec<usize>,
+    advice_index: Vec<usize>,
+    advice_phase: Vec<usize>,
     num_lookup_permuted: usize,
     num_permutation_z: usize,
     num_lookup_z: usize,
 }
 
 impl<'a, F: FieldExt> Polynomials<'a, F> {
-    fn new(cs: &'a ConstraintSystem<F>, n: usize) -> Self {
-        Self {
+    /// Lays out the polynomials of `cs` with its advice columns and user
+    /// challenges assigned to the given phases.
+    ///
+    /// Every phase up to the last one of the advice columns must have an
+    /// advice column, and every challenge must be squeezed after one of them.
+    fn new(
+        cs: &'a ConstraintSystem<F>,
+        n: usize,
+        advice_phase: Vec<usize>,
+        challenge_phase: Vec<usize>,
+    ) -> Result<Self, Error> {
+        if advice_phase.len() != cs.num_advice_columns() {
+            return Err(Error::InvalidPhases(format!(
+                "Expected phases of {} advice columns but got {}",
+                cs.num_advice_columns(),
+                advice_phase.len()
+            )));
+        }
+        let num_phase = advice_phase.iter().max().map_or(1, |phase| phase + 1);
+        if let Some(phase) =
+            (0..num_phase).find(|phase| !advice_phase.is_empty() && !advice_phase.contains(phase))
+        {
+            return Err(Error::InvalidPhases(format!(
+                "Phase {} has no advice columns",
+                phase
+            )));
+        }
+        if let Some(phase) = challenge_phase.iter().find(|phase| **phase >= num_phase) {
+            return Err(Error::InvalidPhases(format!(
+                "Challenge is squeezed after phase {} of {} phases",
+                phase, num_phase
+            )));
+        }
+
+        let advice_index = advice_phase
+            .iter()
+            .enumerate()
+            .map(|(column, phase)| {
+                advice_phase[..column]
+                    .iter()
+                    .filter(|other| *other == phase)
+                    .count()
+            })
+            .collect();
+        let [num_advice, num_challenge] = [&advice_phase, &challenge_phase].map(|phases| {
+            (0..num_phase)
+                .map(|phase| phases.iter().filter(|other| **other == phase).count())
+                .collect::<Vec<_>>()
+        });
+
+        Ok(Self {
             cs,
             n,
             num_fixed: cs.num_fixed_columns(),
             num_permutation_fixed: cs.permutation().get_columns().len(),
             num_instance: cs.num_instance_columns(),
-            num_advice: cs.num_advice_columns(),
+            num_advice,
+            num_challenge,
+            advice_index,
+            advice_phase,
             num_lookup_permuted: 2 * cs.lookups().len(),
             num_permutation_z: cs
                 .permutation()
@@ -81,7 +165,11 @@ impl<'a, F: FieldExt> Polynomials<'a, F> {
                 .len()
                 .div_ceil(cs.degree() - 2),
             num_lookup_z: cs.lookups().len(),
-        }
+        })
+    }
+
+    fn num_phase(&self) -> usize {
+        self.num_advice.len()
     }
 
     fn num_preprocessed(&self) -> usize {
@@ -93,19 +181,24 @@ impl<'a, F: FieldExt> Polynomials<'a, F> {
     }
 
     fn num_auxiliary(&self) -> Vec<usize> {
-        vec![
-            self.n * self.num_advice,
-            self.n * self.num_lookup_permuted,
-            self.n * (self.num_permutation_z + self.num_lookup_z) + 1,
-        ]
+        iter::empty()
+            .chain(self.num_advice.iter().map(|num_advice| self.n * num_advice))
+            .chain([
+                self.n * self.num_lookup_permuted,
+                self.n * (self.num_permutation_z + self.num_lookup_z) + 1,
+            ])
+            .collect()
     }
 
     fn num_challenge(&self) -> Vec<usize> {
-        vec![
-            1, // theta
+        let mut num_challenge = self.num_challenge.clone();
+        // theta is squeezed after the advice of the last phase
+        *num_challenge.last_mut().unwrap() += 1;
+        num_challenge.extend([
             2, // beta, gamma
             0,
-        ]
+        ]);
+        num_challenge
     }
 
     fn instance_offset(&self) -> usize {
@@ -123,24 +216,19 @@ impl<'a, F: FieldExt> Polynomials<'a, F> {
         rotation: R,
         t: usize,
     ) -> Query {
-        let offset = match column_type.into() {
-            Any::Fixed => 0,
-            Any::Instance => self.instance_offset() + t * self.num_instance,
-            Any::Advice => self.auxiliary_offset() + t * self.num_advice,
+        let index = match column_type.into() {
+            Any::Fixed => column_index,
+            Any::Instance => self.instance_offset() + t * self.num_instance + column_index,
+            Any::Advice => {
+                let phase = self.advice_phase[column_index];
+                let phase_offset = self.auxiliary_offset()
+                    + self.num_auxiliary().iter().take(phase).sum::<usize>();
+                phase_offset + t * self.num_advice[phase] + self.advice_index[column_index]
+            }
         };
-        Query::new(offset + column_index, rotation.into())
+        Query::new(index, rotation.into())
     }
 
-    // TODO: Enable this when necessary
-    // fn instance_queries(&'a self, t: usize) -> impl IntoIterator<Item = Query> + 'a {
-    //     self.cs
-    //         .instance_queries()
-    //         .iter()
-    //         .map(move |(column, rotation)| {
-    //             self.query(*column.column_type(), column.index(), *rotation, t)
-    //         })
-    // }
-
     fn advice_queries(&'a self, t: usize) -> impl IntoIterator<Item = Query> + 'a {
         self.cs
             .advice_queries()
@@ -164,7 +252,12 @@ impl<'a, F: FieldExt> Polynomials<'a, F> {
     }
 
     fn permutation_poly(&'a self, t: usize, i: usize) -> usize {
-        let z_offset = self.auxiliary_offset() + self.num_auxiliary().iter().take(2).sum::<usize>();
+        let z_offset = self.auxiliary_offset()
+            + self
+                .num_auxiliary()
+                .iter()
+                .take(self.num_phase() + 1)
+                .sum::<usize>();
         z_offset + t * self.num_permutation_z + i
     }
 
@@ -200,8 +293,15 @@ impl<'a, F: FieldExt> Polynomials<'a, F> {
     }
 
     fn lookup_poly(&'a self, t: usize, i: usize) -> (usize, usize, usize) {
-        let permuted_offset = self.auxiliary_offset() + self.num_auxiliary()[0];
-        let z_offset = permuted_offset + self.num_auxiliary()[1] + self.n * self.num_permutation_z;
+        let permuted_offset = self.auxiliary_offset()
+            + self
+                .num_auxiliary()
+                .iter()
+                .take(self.num_phase())
+                .sum::<usize>();
+        let z_offset = permuted_offset
+            + self.num_auxiliary()[self.num_phase()]
+            + self.n * self.num_permutation_z;
         let z = z_offset + t * self.num_lookup_z + i;
         let permuted_input = permuted_offset + 2 * (t * self.num_lookup_z + i);
         let permuted_table = permuted_input + 1;
@@ -289,16 +389,22 @@ impl<'a, F: FieldExt> Polynomials<'a, F> {
         Expression::Constant(F::one()) - self.l_last() - self.l_blind()
     }
 
+    fn num_user_challenge(&self) -> usize {
+        self.num_challenge.iter().sum()
+    }
+
+    // User challenges are squeezed phase by phase ahead of theta, beta and gamma,
+    // so they occupy the leading `Expression::Challenge` indices.
     fn theta(&self) -> Expression<F> {
-        Expression::Challenge(0)
+        Expression::Challenge(self.num_user_challenge())
     }
 
     fn beta(&self) -> Expression<F> {
-        Expression::Challenge(1)
+        Expression::Challenge(self.num_user_challenge() + 1)
     }
 
     fn gamma(&self) -> Expression<F> {
-        Expression::Challenge(2)
+        Expression::Challenge(self.num_user_challenge() + 2)
     }
 
     fn permutation_relations(&'a self, t: usize) -> impl IntoIterator<Item = Expression<F>> + 'a {
@@ -494,6 +600,35 @@ pub fn compile<C: CurveAffine>(
     n: usize,
     accumulator_indices: Option<Vec<(usize, usize)>>,
 ) -> Protocol<C::CurveExt> {
+    // The pinned halo2 has no notion of phases yet, so its `ConstraintSystem`
+    // has no `advice_column_phase` or `challenge_phase` to read, and every
+    // circuit it builds is single phase: all advice columns live in the first
+    // phase and there are no user challenges, which always lays out.
+    compile_with_phases(
+        vk,
+        n,
+        accumulator_indices,
+        vec![0; vk.cs().num_advice_columns()],
+        Vec::new(),
+    )
+    .unwrap()
+}
+
+/// Like [`compile`], but with advice column `i` committed in phase
+/// `advice_phase[i]` and user challenge `j` squeezed after phase
+/// `challenge_phase[j]`, for circuits whose phases the constraint system
+/// can't tell.
+///
+/// Returns [`Error::InvalidPhases`] if `advice_phase` doesn't have a phase for
+/// each advice column, skips a phase, or a challenge is squeezed after a phase
+/// past the last one.
+pub fn compile_with_phases<C: CurveAffine>(
+    vk: &VerifyingKey<C>,
+    n: usize,
+    accumulator_indices: Option<Vec<(usize, usize)>>,
+    advice_phase: Vec<usize>,
+    challenge_phase: Vec<usize>,
+) -> Result<Protocol<C::CurveExt>, Error> {
     let cs = vk.cs();
 
     let k = vk.get_domain().empty_lagrange().len().log2();
@@ -507,10 +642,9 @@ pub fn compile<C: CurveAffine>(
         .map(Into::into)
         .collect();
 
-    let polynomials = &Polynomials::new(cs, n);
+    let polynomials = &Polynomials::new(cs, n, advice_phase, challenge_phase)?;
 
     let evaluations = iter::empty()
-        // .chain((0..n).flat_map(move |t| polynomials.instance_queries(t)))
         .chain((0..n).flat_map(move |t| polynomials.advice_queries(t)))
         .chain(polynomials.fixed_queries())
         .chain(iter::once(polynomials.random_query()))
@@ -522,7 +656,6 @@ pub fn compile<C: CurveAffine>(
     let queries = (0..n)
         .flat_map(|t| {
             iter::empty()
-                // .chain(polynomials.instance_queries(t))
                 .chain(polynomials.advice_queries(t))
                 .chain(polynomials.permutation_z_queries::<false>(t))
                 .chain(polynomials.lookup_queries::<false>(t))
@@ -547,7 +680,7 @@ pub fn compile<C: CurveAffine>(
     let accumulator_indices = accumulator_indices
         .map(|accumulator_indices| polynomials.accumulator_indices(accumulator_indices));
 
-    Protocol {
+    Ok(Protocol {
         domain,
         preprocessed,
         num_statement: polynomials.num_statement(),
@@ -558,5 +691,95 @@ pub fn compile<C: CurveAffine>(
         relations,
         transcript_initial_state,
         accumulator_indices,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{compile_with_phases, Polynomials};
+    use crate::{
+        test::StandardPlonk,
+        util::{prepare, Query},
+        Error,
+    };
+    use halo2_wrong::curves::bn256::{Bn256, Fr};
+    use halo2_wrong::halo2::{
+        plonk::{Advice, Circuit, ConstraintSystem},
+        poly::kzg::commitment::KZGCommitmentScheme,
+    };
+
+    #[test]
+    fn should_compile_single_phase_layout() {
+        let (_, _, protocol) = prepare::<KZGCommitmentScheme<Bn256>, _>(
+            &StandardPlonk::<Fr>::default(),
+            8,
+            2,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(protocol.num_auxiliary.len(), protocol.num_challenge.len());
+        assert_eq!(protocol.num_auxiliary[0], 2 * 3);
+        assert_eq!(protocol.num_challenge, vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn should_compile_multi_phase_layout() {
+        let (_, pk, _) = prepare::<KZGCommitmentScheme<Bn256>, _>(
+            &StandardPlonk::<Fr>::default(),
+            8,
+            2,
+            None,
+        )
+        .unwrap();
+        let protocol =
+            compile_with_phases(pk.get_vk(), 2, None, vec![0, 1, 1], vec![0]).unwrap();
+
+        assert_eq!(protocol.num_auxiliary.len(), protocol.num_challenge.len());
+        assert_eq!(protocol.num_auxiliary[..2], [2, 2 * 2]);
+        assert_eq!(protocol.num_challenge, vec![1, 1, 2, 0]);
+    }
+
+    #[test]
+    fn should_lay_out_advice_and_challenges_per_phase() {
+        let mut cs = ConstraintSystem::<Fr>::default();
+        StandardPlonk::<Fr>::configure(&mut cs);
+        // Column a in the first phase, b and c in the second one, and a user
+        // challenge squeezed after the first phase
+        let polynomials = Polynomials::new(&cs, 2, vec![0, 1, 1], vec![0]).unwrap();
+
+        assert_eq!(polynomials.num_phase(), 2);
+        assert_eq!(polynomials.num_auxiliary()[..2], [2, 2 * 2]);
+        assert_eq!(polynomials.num_challenge(), vec![1, 1, 2, 0]);
+
+        // 5 fixed, 4 permutation fixed and 2 instance polynomials come first
+        let auxiliary_offset = 5 + 4 + 2;
+        assert_eq!(polynomials.auxiliary_offset(), auxiliary_offset);
+        for (column, t, index) in [(0, 0, 0), (0, 1, 1), (1, 0, 2), (2, 0, 3), (1, 1, 4)] {
+            assert_eq!(
+                polynomials.query(Advice, column, 0, t),
+                Query::new(auxiliary_offset + index, 0)
+            );
+        }
+    }
+
+    #[test]
+    fn should_reject_invalid_phases() {
+        let mut cs = ConstraintSystem::<Fr>::default();
+        StandardPlonk::<Fr>::configure(&mut cs);
+
+        for (advice_phase, challenge_phase) in [
+            // Phase of column c is missing
+            (vec![0, 1], vec![]),
+            // Phase 1 has no advice columns
+            (vec![0, 2, 2], vec![0]),
+            // Challenge is squeezed after the last phase of advice columns
+            (vec![0, 1, 1], vec![2]),
+        ] {
+            assert!(matches!(
+                Polynomials::new(&cs, 2, advice_phase, challenge_phase),
+                Err(Error::InvalidPhases(_))
+            ));
+        }
     }
 }
@@ -0,0 +1,619 @@
+use crate::{
+    protocol::Protocol,
+    util::{
+        big_to_fe, modulus,
+        serialization::{
+            invalid_data, read_ec_point, read_i32, read_scalar, read_u8, read_usize, read_vec,
+            write_ec_point, write_i32, write_scalar, write_usize, write_vec,
+        },
+        CommonPolynomial, Curve, Domain, Expression, GroupEncoding, PrimeField, Query, Rotation,
+    },
+};
+use blake2b_simd::Params;
+use num_bigint::BigUint;
+use serde_json::{json, Value};
+use std::io::{self, Read, Write};
+
+const MAGIC: &[u8; 4] = b"PLNK";
+const VERSION: u8 = 1;
+
+/// Node of an expression flattened in post-order, which refers to its
+/// operands by their index in the flattened list. Expressions are encoded as
+/// such lists, so relations of any depth are encoded and decoded without
+/// recursion.
+enum Node<F> {
+    Constant(F),
+    Identity,
+    Lagrange(i32),
+    Polynomial(Query),
+    Challenge(usize),
+    Negated(usize),
+    Sum(usize, usize),
+    Product(usize, usize),
+    Scaled(usize, F),
+}
+
+impl<C> Protocol<C>
+where
+    C: Curve + GroupEncoding,
+{
+    /// Writes the protocol in the versioned binary format. Scalars are written
+    /// as their canonical representation, points compressed, and all lengths
+    /// and indices as little-endian `u32`.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        write_usize(writer, self.domain.k)?;
+        write_vec(writer, &self.preprocessed, write_ec_point)?;
+        write_usize(writer, self.num_statement)?;
+        write_vec(writer, &self.num_auxiliary, |writer, n| {
+            write_usize(writer, *n)
+        })?;
+        write_vec(writer, &self.num_challenge, |writer, n| {
+            write_usize(writer, *n)
+        })?;
+        write_vec(writer, &self.evaluations, write_query)?;
+        write_vec(writer, &self.queries, write_query)?;
+        write_vec(writer, &self.relations, write_expression)?;
+        write_scalar(writer, &self.transcript_initial_state)?;
+        match &self.accumulator_indices {
+            None => writer.write_all(&[0]),
+            Some(accumulator_indices) => {
+                writer.write_all(&[1])?;
+                write_vec(writer, accumulator_indices, |writer, indices| {
+                    write_vec(writer, indices, |writer, (poly, row)| {
+                        write_usize(writer, *poly)?;
+                        write_usize(writer, *row)
+                    })
+                })
+            }
+        }
+    }
+
+    /// Reads a protocol written by [`Protocol::write`].
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("Invalid protocol magic"));
+        }
+        let version = read_u8(reader)?;
+        if version != VERSION {
+            return Err(invalid_data(format!(
+                "Unsupported protocol version {}",
+                version
+            )));
+        }
+
+        let domain = domain(read_usize(reader)?)?;
+        let preprocessed = read_vec(reader, read_ec_point)?;
+        let num_statement = read_usize(reader)?;
+        let num_auxiliary = read_vec(reader, read_usize)?;
+        let num_challenge = read_vec(reader, read_usize)?;
+        let evaluations = read_vec(reader, read_query)?;
+        let queries = read_vec(reader, read_query)?;
+        let relations = read_vec(reader, read_expression)?;
+        let transcript_initial_state = read_scalar(reader)?;
+        let accumulator_indices = match read_u8(reader)? {
+            0 => None,
+            1 => Some(read_vec(reader, |reader| {
+                read_vec(reader, |reader| {
+                    Ok((read_usize(reader)?, read_usize(reader)?))
+                })
+            })?),
+            _ => return Err(invalid_data("Invalid accumulator indices flag")),
+        };
+
+        Ok(Self {
+            domain,
+            preprocessed,
+            num_statement,
+            num_auxiliary,
+            num_challenge,
+            evaluations,
+            queries,
+            relations,
+            transcript_initial_state,
+            accumulator_indices,
+        })
+    }
+
+    /// Encodes the protocol by [`Protocol::write`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// Blake2b-256 digest of the binary encoding. Since the encoding covers
+    /// every field (domain, preprocessed commitments, queries, relations,
+    /// transcript initial state and accumulator indices), it identifies the
+    /// circuit and verifying key the protocol was compiled from.
+    pub fn digest(&self) -> [u8; 32] {
+        let hash = Params::new()
+            .hash_length(32)
+            .personal(b"Protocol-Digest")
+            .hash(&self.to_bytes());
+        hash.as_bytes().try_into().unwrap()
+    }
+
+    /// Reads a protocol from `bytes`, rejecting any trailing data.
+    pub fn from_bytes(mut bytes: &[u8]) -> io::Result<Self> {
+        let protocol = Self::read(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(invalid_data("Trailing bytes after protocol"));
+        }
+        Ok(protocol)
+    }
+
+    /// Encodes the protocol as JSON, with the same content as the binary
+    /// format and scalars and points as `0x`-prefixed hex of their encoding.
+    pub fn to_json(&self) -> String {
+        let value = json!({
+            "version": VERSION,
+            "k": self.domain.k,
+            "preprocessed": self.preprocessed.iter().map(ec_point_to_json).collect::<Vec<_>>(),
+            "num_statement": self.num_statement,
+            "num_auxiliary": self.num_auxiliary,
+            "num_challenge": self.num_challenge,
+            "evaluations": self.evaluations.iter().map(query_to_json).collect::<Vec<_>>(),
+            "queries": self.queries.iter().map(query_to_json).collect::<Vec<_>>(),
+            "relations": self.relations.iter().map(expression_to_json).collect::<Vec<_>>(),
+            "transcript_initial_state": scalar_to_json(&self.transcript_initial_state),
+            "accumulator_indices": self.accumulator_indices,
+        });
+        serde_json::to_string_pretty(&value).unwrap()
+    }
+
+    /// Decodes a protocol encoded by [`Protocol::to_json`].
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        let value = serde_json::from_str::<Value>(json)
+            .map_err(|err| invalid_data(format!("Invalid protocol JSON: {}", err)))?;
+
+        let version = json_usize(field(&value, "version")?)?;
+        if version != VERSION as usize {
+            return Err(invalid_data(format!(
+                "Unsupported protocol version {}",
+                version
+            )));
+        }
+
+        let accumulator_indices = match field(&value, "accumulator_indices")? {
+            Value::Null => None,
+            value => Some(json_vec(value, |value| {
+                json_vec(value, |value| {
+                    let (poly, row) = json_pair(value)?;
+                    Ok((json_usize(poly)?, json_usize(row)?))
+                })
+            })?),
+        };
+
+        Ok(Self {
+            domain: domain(json_usize(field(&value, "k")?)?)?,
+            preprocessed: json_vec(field(&value, "preprocessed")?, ec_point_from_json)?,
+            num_statement: json_usize(field(&value, "num_statement")?)?,
+            num_auxiliary: json_vec(field(&value, "num_auxiliary")?, json_usize)?,
+            num_challenge: json_vec(field(&value, "num_challenge")?, json_usize)?,
+            evaluations: json_vec(field(&value, "evaluations")?, query_from_json)?,
+            queries: json_vec(field(&value, "queries")?, query_from_json)?,
+            relations: json_vec(field(&value, "relations")?, expression_from_json)?,
+            transcript_initial_state: scalar_from_json(field(&value, "transcript_initial_state")?)?,
+            accumulator_indices,
+        })
+    }
+}
+
+fn domain<F: PrimeField>(k: usize) -> io::Result<Domain<F>> {
+    if k > F::S as usize {
+        return Err(invalid_data(format!(
+            "Domain of size 2^{} is unsupported",
+            k
+        )));
+    }
+    Ok(Domain::new(k))
+}
+
+/// Digest of an ordered list of protocols reduced into the scalar field, so
+/// it fits in a single public input. It's the Blake2b-512 digest of the
+/// concatenated [`Protocol::digest`]s.
+pub fn digest_protocols<'a, C: Curve + GroupEncoding>(
+    protocols: impl IntoIterator<Item = &'a Protocol<C>>,
+) -> C::Scalar {
+    let mut state = Params::new().personal(b"Protocols-Digest").to_state();
+    for protocol in protocols {
+        state.update(&protocol.digest());
+    }
+    big_to_fe(BigUint::from_bytes_le(state.finalize().as_bytes()) % modulus::<C::Scalar>())
+}
+
+/// Flattens `expression` into [`Node`]s in post-order, so the last node is
+/// the root and operands always precede the node using them.
+fn flatten<F: PrimeField>(expression: &Expression<F>) -> Vec<Node<F>> {
+    let mut nodes = Vec::new();
+    // Indices of the flattened operands not yet used by their parent
+    let mut operands = Vec::new();
+    let mut stack = vec![(expression, false)];
+    while let Some((expression, expanded)) = stack.pop() {
+        if !expanded {
+            match expression {
+                Expression::Negated(a) | Expression::Scaled(a, _) => {
+                    stack.extend([(expression, true), (a.as_ref(), false)]);
+                    continue;
+                }
+                Expression::Sum(a, b) | Expression::Product(a, b) => {
+                    stack.extend([(expression, true), (b.as_ref(), false), (a.as_ref(), false)]);
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        let mut operand = || operands.pop().unwrap();
+        let node = match expression {
+            Expression::Constant(scalar) => Node::Constant(*scalar),
+            Expression::CommonPolynomial(CommonPolynomial::Identity) => Node::Identity,
+            Expression::CommonPolynomial(CommonPolynomial::Lagrange(i)) => Node::Lagrange(*i),
+            Expression::Polynomial(query) => Node::Polynomial(*query),
+            Expression::Challenge(index) => Node::Challenge(*index),
+            Expression::Negated(_) => Node::Negated(operand()),
+            Expression::Sum(_, _) => {
+                let b = operand();
+                Node::Sum(operand(), b)
+            }
+            Expression::Product(_, _) => {
+                let b = operand();
+                Node::Product(operand(), b)
+            }
+            Expression::Scaled(_, scalar) => Node::Scaled(operand(), *scalar),
+        };
+        operands.push(nodes.len());
+        nodes.push(node);
+    }
+    nodes
+}
+
+/// Rebuilds the expression flattened into `nodes` by [`flatten`], rejecting
+/// nodes referring to operands that don't precede them or are already used,
+/// and nodes left unused by the root.
+fn unflatten<F: PrimeField>(nodes: Vec<Node<F>>) -> io::Result<Expression<F>> {
+    let mut expressions: Vec<Option<Expression<F>>> = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let mut operand = |index: usize| {
+            expressions
+                .get_mut(index)
+                .and_then(Option::take)
+                .map(Box::new)
+                .ok_or_else(|| invalid_data(format!("Invalid expression operand {}", index)))
+        };
+        let expression = match node {
+            Node::Constant(scalar) => Expression::Constant(scalar),
+            Node::Identity => Expression::CommonPolynomial(CommonPolynomial::Identity),
+            Node::Lagrange(i) => Expression::CommonPolynomial(CommonPolynomial::Lagrange(i)),
+            Node::Polynomial(query) => Expression::Polynomial(query),
+            Node::Challenge(index) => Expression::Challenge(index),
+            Node::Negated(a) => Expression::Negated(operand(a)?),
+            Node::Sum(a, b) => Expression::Sum(operand(a)?, operand(b)?),
+            Node::Product(a, b) => Expression::Product(operand(a)?, operand(b)?),
+            Node::Scaled(a, scalar) => Expression::Scaled(operand(a)?, scalar),
+        };
+        expressions.push(Some(expression));
+    }
+
+    let expression = expressions
+        .pop()
+        .flatten()
+        .ok_or_else(|| invalid_data("Empty expression"))?;
+    if expressions.iter().any(Option::is_some) {
+        return Err(invalid_data("Expression has unused nodes"));
+    }
+    Ok(expression)
+}
+
+fn write_query<W: Write>(writer: &mut W, query: &Query) -> io::Result<()> {
+    write_usize(writer, query.poly)?;
+    write_i32(writer, query.rotation.0)
+}
+
+fn write_expression<W: Write, F: PrimeField>(
+    writer: &mut W,
+    expression: &Expression<F>,
+) -> io::Result<()> {
+    write_vec(writer, &flatten(expression), write_node)
+}
+
+fn write_node<W: Write, F: PrimeField>(writer: &mut W, node: &Node<F>) -> io::Result<()> {
+    match node {
+        Node::Constant(scalar) => {
+            writer.write_all(&[0])?;
+            write_scalar(writer, scalar)
+        }
+        Node::Identity => writer.write_all(&[1]),
+        Node::Lagrange(i) => {
+            writer.write_all(&[2])?;
+            write_i32(writer, *i)
+        }
+        Node::Polynomial(query) => {
+            writer.write_all(&[3])?;
+            write_query(writer, query)
+        }
+        Node::Challenge(index) => {
+            writer.write_all(&[4])?;
+            write_usize(writer, *index)
+        }
+        Node::Negated(a) => {
+            writer.write_all(&[5])?;
+            write_usize(writer, *a)
+        }
+        Node::Sum(a, b) => {
+            writer.write_all(&[6])?;
+            write_usize(writer, *a)?;
+            write_usize(writer, *b)
+        }
+        Node::Product(a, b) => {
+            writer.write_all(&[7])?;
+            write_usize(writer, *a)?;
+            write_usize(writer, *b)
+        }
+        Node::Scaled(a, scalar) => {
+            writer.write_all(&[8])?;
+            write_usize(writer, *a)?;
+            write_scalar(writer, scalar)
+        }
+    }
+}
+
+fn read_query<R: Read>(reader: &mut R) -> io::Result<Query> {
+    Ok(Query::new(read_usize(reader)?, read_i32(reader)?))
+}
+
+fn read_expression<R: Read, F: PrimeField>(reader: &mut R) -> io::Result<Expression<F>> {
+    unflatten(read_vec(reader, read_node)?)
+}
+
+fn read_node<R: Read, F: PrimeField>(reader: &mut R) -> io::Result<Node<F>> {
+    Ok(match read_u8(reader)? {
+        0 => Node::Constant(read_scalar(reader)?),
+        1 => Node::Identity,
+        2 => Node::Lagrange(read_i32(reader)?),
+        3 => Node::Polynomial(read_query(reader)?),
+        4 => Node::Challenge(read_usize(reader)?),
+        5 => Node::Negated(read_usize(reader)?),
+        6 => Node::Sum(read_usize(reader)?, read_usize(reader)?),
+        7 => Node::Product(read_usize(reader)?, read_usize(reader)?),
+        8 => Node::Scaled(read_usize(reader)?, read_scalar(reader)?),
+        tag => return Err(invalid_data(format!("Invalid expression tag {}", tag))),
+    })
+}
+
+fn bytes_to_json(bytes: &[u8]) -> Value {
+    Value::String(format!("0x{}", hex::encode(bytes)))
+}
+
+fn scalar_to_json<F: PrimeField>(scalar: &F) -> Value {
+    bytes_to_json(scalar.to_repr().as_ref())
+}
+
+fn ec_point_to_json<C: GroupEncoding>(ec_point: &C) -> Value {
+    bytes_to_json(ec_point.to_bytes().as_ref())
+}
+
+fn query_to_json(query: &Query) -> Value {
+    json!({ "poly": query.poly, "rotation": query.rotation.0 })
+}
+
+fn expression_to_json<F: PrimeField>(expression: &Expression<F>) -> Value {
+    Value::Array(flatten(expression).iter().map(node_to_json).collect())
+}
+
+fn node_to_json<F: PrimeField>(node: &Node<F>) -> Value {
+    match node {
+        Node::Constant(scalar) => json!({ "constant": scalar_to_json(scalar) }),
+        Node::Identity => json!({ "identity": null }),
+        Node::Lagrange(i) => json!({ "lagrange": i }),
+        Node::Polynomial(query) => json!({ "polynomial": query_to_json(query) }),
+        Node::Challenge(index) => json!({ "challenge": index }),
+        Node::Negated(a) => json!({ "negated": a }),
+        Node::Sum(a, b) => json!({ "sum": [a, b] }),
+        Node::Product(a, b) => json!({ "product": [a, b] }),
+        Node::Scaled(a, scalar) => json!({ "scaled": [a, scalar_to_json(scalar)] }),
+    }
+}
+
+fn field<'a>(value: &'a Value, key: &str) -> io::Result<&'a Value> {
+    value
+        .get(key)
+        .ok_or_else(|| invalid_data(format!("Missing field {}", key)))
+}
+
+fn json_usize(value: &Value) -> io::Result<usize> {
+    value
+        .as_u64()
+        .and_then(|value| u32::try_from(value).ok())
+        .map(|value| value as usize)
+        .ok_or_else(|| invalid_data("Expected u32"))
+}
+
+fn json_i32(value: &Value) -> io::Result<i32> {
+    value
+        .as_i64()
+        .and_then(|value| i32::try_from(value).ok())
+        .ok_or_else(|| invalid_data("Expected i32"))
+}
+
+fn json_array(value: &Value) -> io::Result<&Vec<Value>> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid_data("Expected array"))
+}
+
+fn json_pair(value: &Value) -> io::Result<(&Value, &Value)> {
+    match json_array(value)?.as_slice() {
+        [a, b] => Ok((a, b)),
+        _ => Err(invalid_data("Expected pair")),
+    }
+}
+
+fn json_vec<T>(value: &Value, from_json: impl Fn(&Value) -> io::Result<T>) -> io::Result<Vec<T>> {
+    json_array(value)?.iter().map(from_json).collect()
+}
+
+fn json_bytes<T: Default + AsMut<[u8]>>(value: &Value) -> io::Result<T> {
+    let bytes = value
+        .as_str()
+        .and_then(|value| value.strip_prefix("0x"))
+        .and_then(|value| hex::decode(value).ok())
+        .ok_or_else(|| invalid_data("Expected 0x-prefixed hex string"))?;
+    let mut repr = T::default();
+    if repr.as_mut().len() != bytes.len() {
+        return Err(invalid_data("Invalid encoding length"));
+    }
+    repr.as_mut().copy_from_slice(&bytes);
+    Ok(repr)
+}
+
+fn scalar_from_json<F: PrimeField>(value: &Value) -> io::Result<F> {
+    Option::from(F::from_repr(json_bytes(value)?))
+        .ok_or_else(|| invalid_data("Invalid scalar encoding"))
+}
+
+fn ec_point_from_json<C: GroupEncoding>(value: &Value) -> io::Result<C> {
+    Option::from(C::from_bytes(&json_bytes(value)?))
+        .ok_or_else(|| invalid_data("Invalid elliptic curve point encoding"))
+}
+
+fn query_from_json(value: &Value) -> io::Result<Query> {
+    Ok(Query {
+        poly: json_usize(field(value, "poly")?)?,
+        rotation: Rotation(json_i32(field(value, "rotation")?)?),
+    })
+}
+
+fn expression_from_json<F: PrimeField>(value: &Value) -> io::Result<Expression<F>> {
+    unflatten(json_vec(value, node_from_json)?)
+}
+
+fn node_from_json<F: PrimeField>(value: &Value) -> io::Result<Node<F>> {
+    let (tag, value) = value
+        .as_object()
+        .filter(|object| object.len() == 1)
+        .and_then(|object| object.iter().next())
+        .ok_or_else(|| invalid_data("Expected expression node object with a single variant"))?;
+
+    Ok(match tag.as_str() {
+        "constant" => Node::Constant(scalar_from_json(value)?),
+        "identity" => Node::Identity,
+        "lagrange" => Node::Lagrange(json_i32(value)?),
+        "polynomial" => Node::Polynomial(query_from_json(value)?),
+        "challenge" => Node::Challenge(json_usize(value)?),
+        "negated" => Node::Negated(json_usize(value)?),
+        "sum" => {
+            let (a, b) = json_pair(value)?;
+            Node::Sum(json_usize(a)?, json_usize(b)?)
+        }
+        "product" => {
+            let (a, b) = json_pair(value)?;
+            Node::Product(json_usize(a)?, json_usize(b)?)
+        }
+        "scaled" => {
+            let (a, scalar) = json_pair(value)?;
+            Node::Scaled(json_usize(a)?, scalar_from_json(scalar)?)
+        }
+        tag => return Err(invalid_data(format!("Invalid expression variant {}", tag))),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{expression_from_json, read_expression};
+    use crate::{
+        protocol::Protocol,
+        test::{InstanceQuery, StandardPlonk},
+        util::{prepare, Expression, Query},
+    };
+    use halo2_wrong::curves::bn256::{Bn256, Fr, G1};
+    use halo2_wrong::halo2::{plonk::Circuit, poly::kzg::commitment::KZGCommitmentScheme};
+    use serde_json::json;
+
+    fn protocol<C: Circuit<Fr>>(
+        circuit: &C,
+        accumulator_indices: Option<Vec<(usize, usize)>>,
+    ) -> Protocol<G1> {
+        prepare::<KZGCommitmentScheme<Bn256>, _>(circuit, 8, 2, accumulator_indices)
+            .unwrap()
+            .2
+    }
+
+    #[test]
+    fn should_round_trip_binary() {
+        for protocol in [
+            protocol(&StandardPlonk::<Fr>::default(), None),
+            protocol(&InstanceQuery::<Fr>::default(), Some(vec![(0, 0), (0, 1)])),
+        ] {
+            let bytes = protocol.to_bytes();
+            let decoded = Protocol::<G1>::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded.to_bytes(), bytes);
+            assert_eq!(decoded.accumulator_indices, protocol.accumulator_indices);
+        }
+    }
+
+    #[test]
+    fn should_round_trip_json() {
+        for protocol in [
+            protocol(&StandardPlonk::<Fr>::default(), None),
+            protocol(&InstanceQuery::<Fr>::default(), Some(vec![(0, 0), (0, 1)])),
+        ] {
+            let decoded = Protocol::<G1>::from_json(&protocol.to_json()).unwrap();
+            assert_eq!(decoded.to_bytes(), protocol.to_bytes());
+        }
+    }
+
+    #[test]
+    fn should_round_trip_deeply_nested_relation() {
+        let mut protocol = protocol(&StandardPlonk::<Fr>::default(), None);
+        // Each sum nests the previous one, like the constraints of all gates
+        // folded by a challenge, deeper than serde_json would recurse into
+        let leaf = || Expression::Polynomial(Query::new(0, 0));
+        protocol.relations = vec![(0..1000).fold(leaf(), |acc, _| acc + leaf())];
+
+        let bytes = protocol.to_bytes();
+        let decoded = Protocol::<G1>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        let decoded = Protocol::<G1>::from_json(&protocol.to_json()).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(decoded.digest(), protocol.digest());
+    }
+
+    #[test]
+    fn should_reject_malformed_binary() {
+        let bytes = protocol(&StandardPlonk::<Fr>::default(), None).to_bytes();
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[4] += 1;
+        assert!(Protocol::<G1>::from_bytes(&wrong_version).is_err());
+
+        assert!(Protocol::<G1>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Protocol::<G1>::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+    }
+
+    #[test]
+    fn should_reject_invalid_expression_operands() {
+        // Tag 1 is the identity, 5 negates and 6 sums the operands at the
+        // following little-endian u32 indices
+        for (nodes, value) in [
+            (vec![], json!([])),
+            (vec![vec![5, 0, 0, 0, 0]], json!([{ "negated": 0 }])),
+            (
+                vec![vec![1], vec![6, 0, 0, 0, 0, 0, 0, 0, 0]],
+                json!([{ "identity": null }, { "sum": [0, 0] }]),
+            ),
+            (
+                vec![vec![1], vec![1]],
+                json!([{ "identity": null }, { "identity": null }]),
+            ),
+        ] {
+            let bytes = [(nodes.len() as u32).to_le_bytes().to_vec(), nodes.concat()].concat();
+            assert!(read_expression::<_, Fr>(&mut bytes.as_slice()).is_err());
+            assert!(expression_from_json::<Fr>(&value).is_err());
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use crate::{
-    loader::Loader,
+    loader::{LoadedScalar, Loader},
     protocol::Protocol,
     scheme::msm::MSM,
-    util::{Curve, Transcript},
+    util::{
+        CommonPolynomial, CommonPolynomialEvaluation, Curve, Query, Transcript, TranscriptRead,
+    },
     Error,
 };
 use std::ops::{Add, AddAssign, Mul, MulAssign};
@@ -10,6 +12,9 @@ use std::ops::{Add, AddAssign, Mul, MulAssign};
 pub mod plonk;
 pub mod shplonk;
 
+use plonk::{PlonkAccumulationScheme, PlonkProof};
+use shplonk::{ShplonkAccumulationScheme, ShplonkProof};
+
 pub trait AccumulationScheme<C, L, T, S>
 where
     C: Curve,
@@ -28,6 +33,45 @@ where
     ) -> Result<S::Output, Error>;
 }
 
+/// Multi-open argument a proof was created with, which decides the
+/// [`AccumulationScheme`] used to verify it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MultiOpenScheme {
+    /// [`PlonkAccumulationScheme`], for proofs created with halo2's `ProverGWC`.
+    Plonk,
+    /// [`ShplonkAccumulationScheme`], for proofs created with halo2's `ProverSHPLONK`.
+    Shplonk,
+}
+
+impl MultiOpenScheme {
+    pub fn accumulate<C, L, T, S>(
+        &self,
+        protocol: &Protocol<C>,
+        loader: &L,
+        statements: Vec<Vec<L::LoadedScalar>>,
+        transcript: &mut T,
+        strategy: &mut S,
+    ) -> Result<(), Error>
+    where
+        C: Curve,
+        L: Loader<C>,
+        T: TranscriptRead<C, L>,
+        S: AccumulationStrategy<C, L, T, PlonkProof<C, L>>
+            + AccumulationStrategy<C, L, T, ShplonkProof<C, L>>,
+    {
+        match self {
+            MultiOpenScheme::Plonk => PlonkAccumulationScheme::accumulate(
+                protocol, loader, statements, transcript, strategy,
+            )
+            .map(|_| ()),
+            MultiOpenScheme::Shplonk => ShplonkAccumulationScheme::accumulate(
+                protocol, loader, statements, transcript, strategy,
+            )
+            .map(|_| ()),
+        }
+    }
+}
+
 pub trait AccumulationStrategy<C, L, T, P>
 where
     C: Curve,
@@ -42,8 +86,8 @@ where
         _: &L,
         _: &mut T,
         _: &[Vec<L::LoadedScalar>],
-    ) -> Option<Accumulator<C, L>> {
-        None
+    ) -> Result<Option<Accumulator<C, L>>, Error> {
+        Ok(None)
     }
 
     fn process(
@@ -84,8 +128,12 @@ where
         self.rhs += other.rhs;
     }
 
-    pub fn evaluate(self, g1: C) -> (L::LoadedEcPoint, L::LoadedEcPoint) {
-        (self.lhs.evaluate(g1), self.rhs.evaluate(g1))
+    pub fn into_msms(self) -> (MSM<C, L>, MSM<C, L>) {
+        (self.lhs, self.rhs)
+    }
+
+    pub fn evaluate(self, g1: C) -> Result<(L::LoadedEcPoint, L::LoadedEcPoint), Error> {
+        Ok((self.lhs.evaluate(g1)?, self.rhs.evaluate(g1)?))
     }
 
     pub fn random_linear_combine(
@@ -172,3 +220,34 @@ impl<C: Curve, L: Loader<C>, const LIMBS: usize, const BITS: usize> Default
         Self { accumulator: None }
     }
 }
+
+fn instance_evaluations<C: Curve, L: Loader<C>>(
+    protocol: &Protocol<C>,
+    loader: &L,
+    statements: &[Vec<L::LoadedScalar>],
+    common_poly_eval: &CommonPolynomialEvaluation<C, L>,
+) -> Vec<(Query, L::LoadedScalar)> {
+    protocol
+        .instance_queries()
+        .into_iter()
+        .map(|query| {
+            let statements = &statements[query.poly - protocol.preprocessed.len()];
+            let evaluation = if statements.is_empty() {
+                loader.load_zero()
+            } else {
+                L::LoadedScalar::sum(
+                    &statements
+                        .iter()
+                        .enumerate()
+                        .map(|(i, statement)| {
+                            common_poly_eval
+                                .get(CommonPolynomial::Lagrange(i as i32 - query.rotation.0))
+                                * statement
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            };
+            (query, evaluation)
+        })
+        .collect()
+}
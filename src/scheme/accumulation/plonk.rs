@@ -1,14 +1,13 @@
 use crate::{
-    loader::{LoadedScalar, Loader},
+    loader::{CostMetering, LoadedScalar, Loader},
     protocol::Protocol,
     scheme::{
-        accumulation::{AccumulationScheme, AccumulationStrategy, Accumulator},
+        accumulation::{
+            instance_evaluations, AccumulationScheme, AccumulationStrategy, Accumulator,
+        },
         msm::MSM,
     },
-    util::{
-        CommonPolynomial, CommonPolynomialEvaluation, Curve, Expression, Field, Query, Rotation,
-        TranscriptRead,
-    },
+    util::{CommonPolynomialEvaluation, Curve, Expression, Field, Query, Rotation, TranscriptRead},
     Error,
 };
 use std::{collections::HashMap, iter};
@@ -32,11 +31,15 @@ where
         transcript: &mut T,
         strategy: &mut S,
     ) -> Result<S::Output, Error> {
+        let metering = CostMetering::start(loader, "transcript_read");
         transcript.common_scalar(&loader.load_const(&protocol.transcript_initial_state))?;
 
         let proof = PlonkProof::read(protocol, statements, transcript)?;
         let old_accumulator =
-            strategy.extract_accumulator(protocol, loader, transcript, &proof.statements);
+            strategy.extract_accumulator(protocol, loader, transcript, &proof.statements)?;
+        drop(metering);
+
+        let metering = CostMetering::start(loader, "common_poly_evaluation");
 
         let common_poly_eval = {
             let mut common_poly_eval = CommonPolynomialEvaluation::new(
@@ -53,6 +56,9 @@ where
 
         let commitments = proof.commitments(protocol, loader, &common_poly_eval);
         let evaluations = proof.evaluations(protocol, loader, &common_poly_eval)?;
+        drop(metering);
+
+        let _metering = CostMetering::start(loader, "pairing_prep");
 
         let sets = rotation_sets(protocol);
         let powers_of_u = &proof.u.powers(sets.len());
@@ -63,7 +69,9 @@ where
             sets.iter()
                 .map(|set| set.msm(&commitments, &evaluations, &powers_of_v))
                 .zip(powers_of_u.iter().rev())
-                .map(|(msm, power_of_u)| msm * power_of_u)
+                .map(|(msm, power_of_u)| Ok(msm? * power_of_u))
+                .collect::<Result<Vec<_>, Error>>()?
+                .into_iter()
                 .sum::<MSM<_, _>>()
         };
         let z_omegas = sets.iter().map(|set| {
@@ -114,7 +122,10 @@ impl<C: Curve, L: Loader<C>> PlonkProof<C, L> {
         transcript: &mut T,
     ) -> Result<Self, Error> {
         if statements.len() != protocol.num_statement {
-            return Err(Error::InvalidInstances);
+            return Err(Error::InvalidNumStatements {
+                expected: protocol.num_statement,
+                actual: statements.len(),
+            });
         }
         for statements in statements.iter() {
             for statement in statements.iter() {
@@ -216,33 +227,14 @@ impl<C: Curve, L: Loader<C>> PlonkProof<C, L> {
         loader: &L,
         common_poly_eval: &CommonPolynomialEvaluation<C, L>,
     ) -> Result<HashMap<Query, L::LoadedScalar>, Error> {
-        let statement_evaluations = self.statements.iter().map(|statements| {
-            L::LoadedScalar::sum(
-                &statements
-                    .iter()
-                    .enumerate()
-                    .map(|(i, statement)| {
-                        common_poly_eval.get(CommonPolynomial::Lagrange(i as i32)) * statement
-                    })
-                    .collect::<Vec<_>>(),
-            )
-        });
         let mut evaluations = HashMap::<Query, L::LoadedScalar>::from_iter(
             iter::empty()
-                .chain(
-                    statement_evaluations
-                        .into_iter()
-                        .enumerate()
-                        .map(|(i, evaluation)| {
-                            (
-                                Query {
-                                    poly: protocol.preprocessed.len() + i,
-                                    rotation: Rotation::cur(),
-                                },
-                                evaluation,
-                            )
-                        }),
-                )
+                .chain(instance_evaluations(
+                    protocol,
+                    loader,
+                    &self.statements,
+                    common_poly_eval,
+                ))
                 .chain(
                     protocol
                         .evaluations
@@ -308,20 +300,27 @@ impl RotationSet {
         commitments: &HashMap<usize, MSM<C, L>>,
         evaluations: &HashMap<Query, L::LoadedScalar>,
         powers_of_v: &[L::LoadedScalar],
-    ) -> MSM<C, L> {
-        self.polys
+    ) -> Result<MSM<C, L>, Error> {
+        Ok(self
+            .polys
             .iter()
             .map(|poly| {
-                let commitment = commitments.get(poly).unwrap().clone();
+                let commitment = commitments
+                    .get(poly)
+                    .ok_or(Error::MissingCommitment(*poly))?
+                    .clone();
+                let query = Query::new(*poly, self.rotation);
                 let evalaution = evaluations
-                    .get(&Query::new(*poly, self.rotation))
-                    .unwrap()
+                    .get(&query)
+                    .ok_or(Error::MissingQuery(query))?
                     .clone();
-                commitment - MSM::scalar(evalaution)
+                Ok(commitment - MSM::scalar(evalaution))
             })
+            .collect::<Result<Vec<_>, Error>>()?
+            .into_iter()
             .zip(powers_of_v.iter().take(self.polys.len()).rev())
             .map(|(msm, power_of_v)| msm * power_of_v)
-            .sum()
+            .sum())
     }
 }
 
@@ -1,13 +1,15 @@
 use crate::{
-    loader::{LoadedScalar, Loader},
+    loader::{CostMetering, LoadedScalar, Loader},
     protocol::Protocol,
     scheme::{
-        accumulation::{AccumulationScheme, AccumulationStrategy, Accumulator},
+        accumulation::{
+            instance_evaluations, AccumulationScheme, AccumulationStrategy, Accumulator,
+        },
         msm::MSM,
     },
     util::{
-        CommonPolynomial, CommonPolynomialEvaluation, Curve, Domain, Expression, Field, Fraction,
-        Query, Rotation, TranscriptRead,
+        CommonPolynomialEvaluation, Curve, Domain, Expression, Field, Fraction, Query, Rotation,
+        TranscriptRead,
     },
     Error,
 };
@@ -35,11 +37,15 @@ where
         transcript: &mut T,
         strategy: &mut S,
     ) -> Result<S::Output, Error> {
+        let metering = CostMetering::start(loader, "transcript_read");
         transcript.common_scalar(&loader.load_const(&protocol.transcript_initial_state))?;
 
         let proof = ShplonkProof::read(protocol, statements, transcript)?;
         let old_accumulator =
-            strategy.extract_accumulator(protocol, loader, transcript, &proof.statements);
+            strategy.extract_accumulator(protocol, loader, transcript, &proof.statements)?;
+        drop(metering);
+
+        let metering = CostMetering::start(loader, "common_poly_evaluation");
 
         let (common_poly_eval, sets) = {
             let mut common_poly_eval = CommonPolynomialEvaluation::new(
@@ -62,6 +68,9 @@ where
 
         let commitments = proof.commitments(protocol, loader, &common_poly_eval);
         let evaluations = proof.evaluations(protocol, loader, &common_poly_eval)?;
+        drop(metering);
+
+        let _metering = CostMetering::start(loader, "pairing_prep");
 
         let f = {
             let powers_of_mu = proof
@@ -69,9 +78,11 @@ where
                 .powers(sets.iter().map(|set| set.polys.len()).max().unwrap());
             let msms = sets
                 .iter()
-                .map(|set| set.msm(&commitments, &evaluations, &powers_of_mu));
+                .map(|set| set.msm(&commitments, &evaluations, &powers_of_mu))
+                .collect::<Result<Vec<_>, Error>>()?;
 
-            msms.zip(proof.gamma.powers(sets.len()).into_iter().rev())
+            msms.into_iter()
+                .zip(proof.gamma.powers(sets.len()).into_iter().rev())
                 .map(|(msm, power_of_gamma)| msm * &power_of_gamma)
                 .sum::<MSM<_, _>>()
                 - MSM::base(proof.w.clone()) * &sets[0].z_s
@@ -110,7 +121,10 @@ impl<C: Curve, L: Loader<C>> ShplonkProof<C, L> {
         transcript: &mut T,
     ) -> Result<Self, Error> {
         if statements.len() != protocol.num_statement {
-            return Err(Error::InvalidInstances);
+            return Err(Error::InvalidNumStatements {
+                expected: protocol.num_statement,
+                actual: statements.len(),
+            });
         }
         for statements in statements.iter() {
             for statement in statements.iter() {
@@ -216,34 +230,14 @@ impl<C: Curve, L: Loader<C>> ShplonkProof<C, L> {
         loader: &L,
         common_poly_eval: &CommonPolynomialEvaluation<C, L>,
     ) -> Result<HashMap<Query, L::LoadedScalar>, Error> {
-        let statement_evaluations = self.statements.iter().map(|statements| {
-            L::LoadedScalar::sum(
-                &statements
-                    .iter()
-                    .enumerate()
-                    .map(|(i, statement)| {
-                        statement.clone()
-                            * common_poly_eval.get(CommonPolynomial::Lagrange(i as i32))
-                    })
-                    .collect::<Vec<_>>(),
-            )
-        });
         let mut evaluations = HashMap::<Query, L::LoadedScalar>::from_iter(
             iter::empty()
-                .chain(
-                    statement_evaluations
-                        .into_iter()
-                        .enumerate()
-                        .map(|(i, evaluation)| {
-                            (
-                                Query {
-                                    poly: protocol.preprocessed.len() + i,
-                                    rotation: Rotation::cur(),
-                                },
-                                evaluation,
-                            )
-                        }),
-                )
+                .chain(instance_evaluations(
+                    protocol,
+                    loader,
+                    &self.statements,
+                    common_poly_eval,
+                ))
                 .chain(
                     protocol
                         .evaluations
@@ -421,18 +415,20 @@ impl<C: Curve, L: Loader<C>> IntermediateSet<C, L> {
         commitments: &HashMap<usize, MSM<C, L>>,
         evaluations: &HashMap<Query, L::LoadedScalar>,
         powers_of_mu: &[L::LoadedScalar],
-    ) -> MSM<C, L> {
-        self.polys
+    ) -> Result<MSM<C, L>, Error> {
+        Ok(self
+            .polys
             .iter()
             .zip(powers_of_mu.iter().take(self.polys.len()).rev())
             .map(|(poly, power_of_mu)| {
-                let commitment = self
-                    .commitment_coeff
-                    .as_ref()
-                    .map(|commitment_coeff| {
-                        commitments.get(poly).unwrap().clone() * &commitment_coeff.evaluate()
-                    })
-                    .unwrap_or_else(|| commitments.get(poly).unwrap().clone());
+                let commitment = commitments
+                    .get(poly)
+                    .ok_or(Error::MissingCommitment(*poly))?
+                    .clone();
+                let commitment = match self.commitment_coeff.as_ref() {
+                    Some(commitment_coeff) => commitment * &commitment_coeff.evaluate(),
+                    None => commitment,
+                };
                 let remainder = self.remainder_coeff.as_ref().unwrap().evaluate()
                     * L::LoadedScalar::sum(
                         &self
@@ -440,19 +436,17 @@ impl<C: Curve, L: Loader<C>> IntermediateSet<C, L> {
                             .iter()
                             .zip(self.evaluation_coeffs.iter())
                             .map(|(rotation, coeff)| {
-                                coeff.evaluate()
-                                    * evaluations
-                                        .get(&Query {
-                                            poly: *poly,
-                                            rotation: *rotation,
-                                        })
-                                        .unwrap()
+                                let query = Query::new(*poly, *rotation);
+                                Ok(coeff.evaluate()
+                                    * evaluations.get(&query).ok_or(Error::MissingQuery(query))?)
                             })
-                            .collect::<Vec<_>>(),
+                            .collect::<Result<Vec<_>, Error>>()?,
                     );
-                (commitment - MSM::scalar(remainder)) * power_of_mu
+                Ok((commitment - MSM::scalar(remainder)) * power_of_mu)
             })
-            .sum()
+            .collect::<Result<Vec<_>, Error>>()?
+            .into_iter()
+            .sum())
     }
 }
 
@@ -2,7 +2,8 @@ mod accumulation;
 mod msm;
 
 pub use accumulation::{
-    plonk::PlonkAccumulationScheme, shplonk::ShplonkAccumulationScheme, AccumulationScheme,
-    AccumulationStrategy, Accumulator, SameCurveAccumulation,
+    plonk::{PlonkAccumulationScheme, PlonkProof},
+    shplonk::{ShplonkAccumulationScheme, ShplonkProof},
+    AccumulationScheme, AccumulationStrategy, Accumulator, MultiOpenScheme, SameCurveAccumulation,
 };
 pub use msm::MSM;
@@ -1,6 +1,7 @@
 use crate::{
     loader::{LoadedEcPoint, Loader},
     util::Curve,
+    Error,
 };
 use std::{
     default::Default,
@@ -42,11 +43,42 @@ impl<C: Curve, L: Loader<C>> MSM<C, L> {
         }
     }
 
-    pub fn evaluate(self, gen: C) -> L::LoadedEcPoint {
+    /// Splits into the scalar of the generator and the scalar-base pairs.
+    #[allow(clippy::type_complexity)]
+    pub fn into_parts(
+        self,
+    ) -> (
+        Option<L::LoadedScalar>,
+        Vec<(L::LoadedScalar, L::LoadedEcPoint)>,
+    ) {
+        (
+            self.scalar,
+            self.scalars.into_iter().zip(self.bases).collect(),
+        )
+    }
+
+    /// Inverse of [`MSM::into_parts`].
+    pub fn from_parts(
+        scalar: Option<L::LoadedScalar>,
+        pairs: impl IntoIterator<Item = (L::LoadedScalar, L::LoadedEcPoint)>,
+    ) -> Self {
+        let mut msm = MSM {
+            scalar,
+            ..Default::default()
+        };
+        for (scalar, base) in pairs {
+            msm.push(scalar, base);
+        }
+        msm
+    }
+
+    /// Returns [`Error::EmptyMsm`] if there are no bases, even if there's a
+    /// scalar of `gen`, as a loader to load it with is taken from the bases.
+    pub fn evaluate(self, gen: C) -> Result<L::LoadedEcPoint, Error> {
         let gen = self
             .bases
             .first()
-            .unwrap()
+            .ok_or(Error::EmptyMsm)?
             .loader()
             .ec_point_load_const(&gen);
         L::LoadedEcPoint::multi_scalar_multiplication(
@@ -0,0 +1,353 @@
+use crate::{
+    collect_slice,
+    loader::native::{ChallengeScalar, PoseidonTranscript},
+    native::Snark,
+    scheme::MultiOpenScheme,
+    util::{accumulate_snark, prepare_with_params},
+};
+use halo2_wrong::curves::bn256::{Bn256, Fr, G1Affine, G1};
+use halo2_wrong::halo2::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Fixed, Instance},
+    poly::{
+        commitment::CommitmentScheme,
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::{ProverGWC, ProverSHPLONK},
+        },
+        Rotation,
+    },
+    transcript::TranscriptWriterBuffer,
+};
+use rand::{rngs::OsRng, RngCore};
+
+/// Size of the params app snarks are proven under.
+pub const APP_K: u32 = 8;
+
+#[derive(Clone, Copy)]
+pub struct StandardPlonkConfig {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    c: Column<Advice>,
+    q_a: Column<Fixed>,
+    q_b: Column<Fixed>,
+    q_c: Column<Fixed>,
+    q_ab: Column<Fixed>,
+    constant: Column<Fixed>,
+    instance: Column<Instance>,
+}
+
+impl StandardPlonkConfig {
+    fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> Self {
+        let [a, b, c] = [(); 3].map(|_| meta.advice_column());
+        let [q_a, q_b, q_c, q_ab, constant] = [(); 5].map(|_| meta.fixed_column());
+        let instance = meta.instance_column();
+
+        [a, b, c].map(|column| meta.enable_equality(column));
+        meta.enable_equality(instance);
+
+        meta.create_gate(
+            "q_a·a + q_b·b + q_c·c + q_ab·a·b + constant = 0",
+            |meta| {
+                let [a, b, c] = [a, b, c].map(|column| meta.query_advice(column, Rotation::cur()));
+                let [q_a, q_b, q_c, q_ab, constant] = [q_a, q_b, q_c, q_ab, constant]
+                    .map(|column| meta.query_fixed(column, Rotation::cur()));
+                vec![q_a * a.clone() + q_b * b.clone() + q_c * c + q_ab * a * b + constant]
+            },
+        );
+
+        StandardPlonkConfig {
+            a,
+            b,
+            c,
+            q_a,
+            q_b,
+            q_c,
+            q_ab,
+            constant,
+            instance,
+        }
+    }
+}
+
+/// Proves knowledge of `x` with `x^2 + 5` equal to the single public instance.
+#[derive(Clone, Default)]
+pub struct StandardPlonk<F: FieldExt>(F);
+
+impl<F: FieldExt> StandardPlonk<F> {
+    pub fn rand<R: RngCore>(mut rng: R) -> Self {
+        Self(F::from(rng.next_u32() as u64))
+    }
+
+    pub fn instances(&self) -> Vec<Vec<F>> {
+        vec![vec![self.0.square() + F::from(5)]]
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for StandardPlonk<F> {
+    type Config = StandardPlonkConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        StandardPlonkConfig::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let output = layouter.assign_region(
+            || "",
+            |mut region| {
+                // a * b - c = 0 with b copied from a
+                let a = region.assign_advice(|| "", config.a, 0, || Value::known(self.0))?;
+                a.copy_advice(|| "", &mut region, config.b, 0)?;
+                let c =
+                    region.assign_advice(|| "", config.c, 0, || Value::known(self.0.square()))?;
+                region.assign_fixed(|| "", config.q_ab, 0, || Value::known(F::one()))?;
+                region.assign_fixed(|| "", config.q_c, 0, || Value::known(-F::one()))?;
+
+                // a + 5 - c = 0 with a copied from the previous c
+                c.copy_advice(|| "", &mut region, config.a, 1)?;
+                let output = region.assign_advice(
+                    || "",
+                    config.c,
+                    1,
+                    || Value::known(self.0.square() + F::from(5)),
+                )?;
+                region.assign_fixed(|| "", config.q_a, 1, || Value::known(F::one()))?;
+                region.assign_fixed(|| "", config.q_c, 1, || Value::known(-F::one()))?;
+                region.assign_fixed(|| "", config.constant, 1, || Value::known(F::from(5)))?;
+
+                Ok(output)
+            },
+        )?;
+
+        layouter.constrain_instance(output.cell(), config.instance, 0)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct InstanceQueryConfig {
+    a: Column<Advice>,
+    q: Column<Fixed>,
+    instance: Column<Instance>,
+}
+
+/// Proves `a = instances[0] + instances[2]` with a gate querying the instance
+/// column at the previous and next rotation, instead of copying from it.
+#[derive(Clone, Default)]
+pub struct InstanceQuery<F: FieldExt>([F; 3]);
+
+impl<F: FieldExt> InstanceQuery<F> {
+    pub fn rand<R: RngCore>(mut rng: R) -> Self {
+        Self([(); 3].map(|_| F::from(rng.next_u32() as u64)))
+    }
+
+    pub fn instances(&self) -> Vec<Vec<F>> {
+        vec![self.0.to_vec()]
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for InstanceQuery<F> {
+    type Config = InstanceQueryConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let a = meta.advice_column();
+        let q = meta.fixed_column();
+        let instance = meta.instance_column();
+
+        meta.create_gate("q·(a - instance_prev - instance_next) = 0", |meta| {
+            let a = meta.query_advice(a, Rotation::cur());
+            let q = meta.query_fixed(q, Rotation::cur());
+            let prev = meta.query_instance(instance, Rotation::prev());
+            let next = meta.query_instance(instance, Rotation::next());
+            vec![q * (a - prev - next)]
+        });
+
+        InstanceQueryConfig { a, q, instance }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "",
+            |mut region| {
+                region.assign_advice(|| "", config.a, 1, || Value::known(self.0[0] + self.0[2]))?;
+                region.assign_fixed(|| "", config.q, 1, || Value::known(F::one()))?;
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Exposes its values as public instances without constraining them
+/// otherwise, e.g. to carry an accumulator without proving its aggregation.
+#[derive(Clone, Default)]
+pub struct Passthrough<F: FieldExt>(Vec<F>);
+
+impl<F: FieldExt> Passthrough<F> {
+    pub fn new(values: Vec<F>) -> Self {
+        Self(values)
+    }
+
+    pub fn instances(&self) -> Vec<Vec<F>> {
+        vec![self.0.clone()]
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for Passthrough<F> {
+    type Config = StandardPlonkConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self(vec![F::zero(); self.0.len()])
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        StandardPlonkConfig::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        // Selectors are left zero, so the gate holds whatever the values
+        let cells = layouter.assign_region(
+            || "",
+            |mut region| {
+                self.0
+                    .iter()
+                    .enumerate()
+                    .map(|(row, value)| {
+                        region.assign_advice(|| "", config.a, row, || Value::known(*value))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )?;
+
+        for (row, cell) in cells.iter().enumerate() {
+            layouter.constrain_instance(cell.cell(), config.instance, row)?;
+        }
+        Ok(())
+    }
+}
+
+/// Proves each circuit with its instances under `params`, with transcript
+/// `TW` and the prover of its multi-open scheme, sharing the protocol
+/// compiled from the keys of the first circuit.
+pub fn create_snarks<C, TW>(
+    params: &ParamsKZG<Bn256>,
+    accumulator_indices: Option<Vec<(usize, usize)>>,
+    circuits: Vec<(C, Vec<Vec<Fr>>, MultiOpenScheme)>,
+) -> Vec<Snark<G1>>
+where
+    C: Circuit<Fr>,
+    TW: TranscriptWriterBuffer<Vec<u8>, G1Affine, ChallengeScalar<G1Affine>>,
+{
+    let (pk, protocol) = prepare_with_params::<KZGCommitmentScheme<Bn256>, _>(
+        params,
+        &circuits[0].0,
+        1,
+        accumulator_indices,
+    )
+    .unwrap();
+    circuits
+        .into_iter()
+        .map(|(circuit, instances, scheme)| {
+            collect_slice!(instances);
+            match scheme {
+                MultiOpenScheme::Plonk => accumulate_snark::<
+                    KZGCommitmentScheme<Bn256>,
+                    ChallengeScalar<G1Affine>,
+                    TW,
+                    ProverGWC<_>,
+                    _,
+                    _,
+                >(
+                    params,
+                    &pk,
+                    &[circuit],
+                    &[instances.as_slice()],
+                    protocol.clone(),
+                    OsRng,
+                ),
+                MultiOpenScheme::Shplonk => accumulate_snark::<
+                    KZGCommitmentScheme<Bn256>,
+                    ChallengeScalar<G1Affine>,
+                    TW,
+                    ProverSHPLONK<_>,
+                    _,
+                    _,
+                >(
+                    params,
+                    &pk,
+                    &[circuit],
+                    &[instances.as_slice()],
+                    protocol.clone(),
+                    OsRng,
+                ),
+            }
+            .unwrap()
+        })
+        .collect()
+}
+
+/// Proves a random [`StandardPlonk`] for each of `schemes` under `params`,
+/// sharing a protocol, with transcript `TW`.
+pub fn standard_plonk_snarks<TW>(
+    params: &ParamsKZG<Bn256>,
+    schemes: &[MultiOpenScheme],
+) -> Vec<Snark<G1>>
+where
+    TW: TranscriptWriterBuffer<Vec<u8>, G1Affine, ChallengeScalar<G1Affine>>,
+{
+    create_snarks::<_, TW>(
+        params,
+        None,
+        schemes
+            .iter()
+            .map(|scheme| {
+                let circuit = StandardPlonk::<Fr>::rand(OsRng);
+                let instances = circuit.instances();
+                (circuit, instances, *scheme)
+            })
+            .collect(),
+    )
+}
+
+/// Like [`standard_plonk_snarks`], but proves a single [`StandardPlonk`].
+pub fn standard_plonk_snark<TW>(params: &ParamsKZG<Bn256>, scheme: MultiOpenScheme) -> Snark<G1>
+where
+    TW: TranscriptWriterBuffer<Vec<u8>, G1Affine, ChallengeScalar<G1Affine>>,
+{
+    standard_plonk_snarks::<TW>(params, &[scheme])
+        .pop()
+        .unwrap()
+}
+
+/// Returns params of size `2^APP_K` to prove app snarks under.
+pub fn app_params() -> ParamsKZG<Bn256> {
+    KZGCommitmentScheme::<Bn256>::new_params(APP_K)
+}
+
+/// Proves a random [`StandardPlonk`] under `params` with the transcript and
+/// multi-open scheme [`Accumulator`](crate::circuit::Accumulator) expects.
+pub fn app_snark(params: &ParamsKZG<Bn256>) -> Snark<G1> {
+    standard_plonk_snark::<PoseidonTranscript<_, _>>(params, MultiOpenScheme::Shplonk)
+}
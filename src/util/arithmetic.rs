@@ -203,6 +203,18 @@ impl<F: FieldOps + Clone> Fraction<F> {
     }
 }
 
+pub fn modulus<F: PrimeField>() -> BigUint {
+    fe_to_big(-F::one()) + 1usize
+}
+
+pub fn fe_to_big<F: PrimeField>(fe: F) -> BigUint {
+    BigUint::from_bytes_le(fe.to_repr().as_ref())
+}
+
+pub fn fe_to_fe<F1: PrimeField, F2: PrimeField>(fe: F1) -> F2 {
+    big_to_fe(fe_to_big(fe) % modulus::<F2>())
+}
+
 pub fn big_to_fe<F: PrimeField>(big: BigUint) -> F {
     let bytes = big.to_bytes_le();
     let mut repr = F::Repr::default();
@@ -239,6 +239,20 @@ impl<F: Clone> Expression<F> {
         )
         .unwrap_or_default()
     }
+
+    pub fn used_query(&self) -> BTreeSet<Query> {
+        self.evaluate(
+            &|_| None,
+            &|_| None,
+            &|query| Some(BTreeSet::from_iter([query])),
+            &|_| None,
+            &|a| a,
+            &merge_left_right,
+            &merge_left_right,
+            &|a, _| a,
+        )
+        .unwrap_or_default()
+    }
 }
 
 impl<F: Clone> From<Query> for Expression<F> {
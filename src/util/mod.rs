@@ -1,34 +1,48 @@
 mod arithmetic;
 mod expression;
 mod transcript;
+pub(crate) mod serialization;
 
-use std::fmt::Debug;
+use std::{fmt::Debug, io::{self, Cursor}};
 use crate::protocol::Protocol;
 use rand::RngCore;
 
-use crate::protocol::compile;
+use crate::protocol::{compile, transcript_initial_state};
+use crate::Error;
 
 pub use arithmetic::{
-    batch_invert, batch_invert_and_mul, fe_from_limbs, fe_to_limbs, Curve, Domain, Field, FieldOps,
-    Fraction, Group, GroupEncoding, GroupOps, PrimeCurveAffine, PrimeField, Rotation,
-    UncompressedEncoding,
+    batch_invert, batch_invert_and_mul, big_to_fe, fe_from_limbs, fe_to_big, fe_to_fe, fe_to_limbs,
+    modulus, Curve, Domain, Field, FieldOps, Fraction, Group, GroupEncoding, GroupOps,
+    PrimeCurveAffine, PrimeField, Rotation, UncompressedEncoding,
 };
 pub use expression::{CommonPolynomial, CommonPolynomialEvaluation, Expression, Query};
 use halo2_wrong::{
 	halo2::{
-		plonk::{keygen_pk, keygen_vk, Circuit, create_proof},
-		poly::commitment::CommitmentScheme,
-		transcript::{EncodedChallenge, TranscriptWriterBuffer},
+		plonk::{keygen_pk, keygen_vk, verify_proof, Circuit, create_proof},
+		poly::{
+			commitment::{CommitmentScheme, ParamsProver, Verifier},
+			kzg::{
+				commitment::KZGCommitmentScheme,
+				multiopen::{VerifierGWC, VerifierSHPLONK},
+				strategy::SingleStrategy,
+			},
+			VerificationStrategy,
+		},
+		transcript::{EncodedChallenge, TranscriptReadBuffer, TranscriptWriterBuffer},
 	},
-	curves::{pairing::Engine, CurveAffine},
+	curves::{pairing::MultiMillerLoop, CurveAffine},
 
 };
 use halo2_wrong_ecc::halo2::{
-	poly::commitment::Prover,
+	poly::{
+		commitment::Prover,
+		kzg::multiopen::{ProverGWC, ProverSHPLONK},
+	},
 	plonk::ProvingKey
 };
 pub use transcript::{Transcript, TranscriptRead};
 use crate::native::Snark;
+use crate::scheme::MultiOpenScheme;
 
 #[macro_export]
 macro_rules! hex {
@@ -59,28 +73,76 @@ macro_rules! collect_slice {
     };
 }
 
-pub fn prepare<E: Engine + Debug, S: CommitmentScheme, C: Circuit<S::Scalar>>(
+/// Links a halo2 multi-open prover to the scheme verifying its proofs, and to
+/// halo2's own verifier of them.
+pub trait MultiOpenProver<'params, S: CommitmentScheme>: Prover<'params, S> {
+	const SCHEME: MultiOpenScheme;
+	type Verifier: Verifier<'params, S>;
+	type Strategy: VerificationStrategy<'params, S, Self::Verifier, Output = ()>;
+}
+
+impl<'params, E: MultiMillerLoop + Debug> MultiOpenProver<'params, KZGCommitmentScheme<E>>
+	for ProverGWC<'params, E>
+{
+	const SCHEME: MultiOpenScheme = MultiOpenScheme::Plonk;
+	type Verifier = VerifierGWC<'params, E>;
+	type Strategy = SingleStrategy<'params, E>;
+}
+
+impl<'params, E: MultiMillerLoop + Debug> MultiOpenProver<'params, KZGCommitmentScheme<E>>
+	for ProverSHPLONK<'params, E>
+{
+	const SCHEME: MultiOpenScheme = MultiOpenScheme::Shplonk;
+	type Verifier = VerifierSHPLONK<'params, E>;
+	type Strategy = SingleStrategy<'params, E>;
+}
+
+/// Reads prover params written by halo2, e.g. the ones of a trusted setup.
+pub fn load_params<S: CommitmentScheme, R: io::Read>(
+	reader: &mut R,
+) -> Result<S::ParamsProver, Error> {
+	S::read_params(reader).map_err(|err| Error::InvalidParams(err.to_string()))
+}
+
+/// Generates fresh params of size `2^k` from a toy setup, only meant for
+/// testing, and then runs [`prepare_with_params`] with them.
+pub fn prepare<S: CommitmentScheme, C: Circuit<S::Scalar>>(
 	circuit: &C,
 	k: u32,
 	n: usize,
 	accumulator_indices: Option<Vec<(usize, usize)>>
-) -> (S::ParamsProver, ProvingKey<S::Curve>, Protocol<<S::Curve as CurveAffine>::CurveExt>) {
+) -> Result<
+	(S::ParamsProver, ProvingKey<S::Curve>, Protocol<<S::Curve as CurveAffine>::CurveExt>),
+	Error,
+> {
 	let params = S::new_params(k);
-	let vk = keygen_vk::<S, _>(&params, circuit).unwrap();
-	let pk = keygen_pk::<S, _>(&params, vk, circuit).unwrap();
+	let (pk, protocol) = prepare_with_params::<S, _>(&params, circuit, n, accumulator_indices)?;
+
+	Ok((params, pk, protocol))
+}
+
+/// Generates the proving key of `circuit` under `params`, and compiles its
+/// verifying key into a protocol.
+pub fn prepare_with_params<S: CommitmentScheme, C: Circuit<S::Scalar>>(
+	params: &S::ParamsProver,
+	circuit: &C,
+	n: usize,
+	accumulator_indices: Option<Vec<(usize, usize)>>
+) -> Result<(ProvingKey<S::Curve>, Protocol<<S::Curve as CurveAffine>::CurveExt>), Error> {
+	let vk = keygen_vk::<S, _>(params, circuit).map_err(|err| Error::Keygen(format!("{:?}", err)))?;
+	let pk = keygen_pk::<S, _>(params, vk, circuit).map_err(|err| Error::Keygen(format!("{:?}", err)))?;
 
 	let protocol = compile::<S::Curve>(pk.get_vk(), n, accumulator_indices);
 
-	(params, pk, protocol)
+	Ok((pk, protocol))
 }
 
 pub fn accumulate_snark<
 	'a,
-	E: Engine,
 	S: CommitmentScheme,
 	EC: EncodedChallenge<S::Curve>,
 	TW: TranscriptWriterBuffer<Vec<u8>, S::Curve, EC>,
-	P: Prover<'a, S>,
+	P: MultiOpenProver<'a, S>,
 	C: Circuit<S::Scalar>,
 	R: RngCore,
 >(
@@ -90,7 +152,20 @@ pub fn accumulate_snark<
 	instances: &[&[&[S::Scalar]]],
 	protocol: Protocol<<S::Curve as CurveAffine>::CurveExt>,
 	mut rng: R,
-) -> Snark<<S::Curve as CurveAffine>::CurveExt> {
+) -> Result<Snark<<S::Curve as CurveAffine>::CurveExt>, Error> {
+	if protocol.transcript_initial_state != transcript_initial_state(pk.get_vk()) {
+		return Err(Error::ProtocolMismatch(
+			"protocol is not compiled from the proving key".to_string(),
+		));
+	}
+	let num_statement = instances.iter().map(|instances| instances.len()).sum::<usize>();
+	if protocol.num_statement != num_statement {
+		return Err(Error::InvalidNumStatements {
+			expected: protocol.num_statement,
+			actual: num_statement,
+		});
+	}
+
 	let mut transcript = TW::init(Vec::new());
 	create_proof::<S, P, _, _, _, _>(
 		params,
@@ -100,7 +175,7 @@ pub fn accumulate_snark<
 		&mut rng,
 		&mut transcript,
 	)
-	.unwrap();
+	.map_err(|err| Error::Proving(format!("{:?}", err)))?;
 	let proof = transcript.finalize();
 
 	let instances_vec = instances
@@ -114,9 +189,104 @@ pub fn accumulate_snark<
 			.collect::<Vec<_>>()
 		)
 		.collect::<Vec<_>>();
-	Snark::new(
+	Ok(Snark::new(
 		protocol,
+		P::SCHEME,
 		instances_vec.into_iter().flatten().collect::<Vec<_>>(),
 		proof,
+	))
+}
+
+/// Same as [`accumulate_snark`], but also checks the proof with halo2's
+/// verifier before wrapping it, failing with [`Error::Proving`] if rejected.
+pub fn accumulate_verified_snark<
+	'a,
+	S: CommitmentScheme,
+	EC: EncodedChallenge<S::Curve>,
+	TW: TranscriptWriterBuffer<Vec<u8>, S::Curve, EC>,
+	TR: TranscriptReadBuffer<Cursor<Vec<u8>>, S::Curve, EC>,
+	P: MultiOpenProver<'a, S>,
+	C: Circuit<S::Scalar>,
+	R: RngCore,
+>(
+	params: &'a S::ParamsProver,
+	pk: &ProvingKey<S::Curve>,
+	circuits: &[C],
+	instances: &[&[&[S::Scalar]]],
+	protocol: Protocol<<S::Curve as CurveAffine>::CurveExt>,
+	rng: R,
+) -> Result<Snark<<S::Curve as CurveAffine>::CurveExt>, Error> {
+	let snark = accumulate_snark::<S, EC, TW, P, C, R>(params, pk, circuits, instances, protocol, rng)?;
+
+	let verifier_params = params.verifier_params();
+	let mut transcript = TR::init(Cursor::new(snark.proof.clone()));
+	verify_proof::<S, P::Verifier, EC, TR, P::Strategy>(
+		verifier_params,
+		pk.get_vk(),
+		P::Strategy::new(verifier_params),
+		instances,
+		&mut transcript,
 	)
-}
\ No newline at end of file
+	.map_err(|err| Error::Proving(format!("Proof is rejected: {:?}", err)))?;
+
+	Ok(snark)
+}
+
+#[cfg(test)]
+mod test {
+	use super::{accumulate_verified_snark, load_params, prepare_with_params};
+	use crate::{
+		circuit::{BITS, LIMBS},
+		collect_slice,
+		loader::native::{ChallengeScalar, PoseidonTranscript},
+		test::{StandardPlonk, APP_K},
+		Error,
+	};
+	use halo2_wrong::curves::bn256::{Bn256, Fr, G1Affine};
+	use halo2_wrong::halo2::poly::{
+		commitment::Params,
+		kzg::{
+			commitment::{KZGCommitmentScheme, ParamsKZG},
+			multiopen::ProverSHPLONK,
+		},
+	};
+	use rand::rngs::OsRng;
+
+	#[test]
+	fn should_accept_verified_proof_with_loaded_params() {
+		let params = {
+			let mut bytes = Vec::new();
+			ParamsKZG::<Bn256>::setup(APP_K, OsRng).write(&mut bytes).unwrap();
+			load_params::<KZGCommitmentScheme<Bn256>, _>(&mut bytes.as_slice()).unwrap()
+		};
+
+		let circuit = StandardPlonk::<Fr>::rand(OsRng);
+		let instances = circuit.instances();
+		collect_slice!(instances);
+
+		let (pk, protocol) =
+			prepare_with_params::<KZGCommitmentScheme<Bn256>, _>(&params, &circuit, 1, None).unwrap();
+		let snark = accumulate_verified_snark::<
+			KZGCommitmentScheme<Bn256>,
+			ChallengeScalar<G1Affine>,
+			PoseidonTranscript<G1Affine, _>,
+			PoseidonTranscript<G1Affine, _>,
+			ProverSHPLONK<_>,
+			_,
+			_,
+		>(&params, &pk, &[circuit], &[instances.as_slice()], protocol, OsRng)
+		.unwrap();
+		assert!(snark.verify::<Bn256, LIMBS, BITS>(&params).is_ok());
+	}
+
+	#[test]
+	fn should_reject_truncated_params() {
+		let mut bytes = Vec::new();
+		ParamsKZG::<Bn256>::setup(APP_K, OsRng).write(&mut bytes).unwrap();
+		bytes.truncate(bytes.len() / 2);
+		assert!(matches!(
+			load_params::<KZGCommitmentScheme<Bn256>, _>(&mut bytes.as_slice()),
+			Err(Error::InvalidParams(_))
+		));
+	}
+}
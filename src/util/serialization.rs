@@ -0,0 +1,78 @@
+use crate::util::{GroupEncoding, PrimeField};
+use std::io::{self, Read, Write};
+
+pub(crate) fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+pub(crate) fn write_usize<W: Write>(writer: &mut W, value: usize) -> io::Result<()> {
+    let value = u32::try_from(value).map_err(|_| invalid_data("Value exceeds u32"))?;
+    write_u32(writer, value)
+}
+
+pub(crate) fn write_i32<W: Write>(writer: &mut W, value: i32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+pub(crate) fn write_scalar<W: Write, F: PrimeField>(writer: &mut W, scalar: &F) -> io::Result<()> {
+    writer.write_all(scalar.to_repr().as_ref())
+}
+
+pub(crate) fn write_ec_point<W: Write, C: GroupEncoding>(
+    writer: &mut W,
+    ec_point: &C,
+) -> io::Result<()> {
+    writer.write_all(ec_point.to_bytes().as_ref())
+}
+
+pub(crate) fn write_vec<W: Write, T>(
+    writer: &mut W,
+    values: &[T],
+    write: impl Fn(&mut W, &T) -> io::Result<()>,
+) -> io::Result<()> {
+    write_usize(writer, values.len())?;
+    values.iter().try_for_each(|value| write(writer, value))
+}
+
+pub(crate) fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut bytes = [0; 1];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes[0])
+}
+
+pub(crate) fn read_usize<R: Read>(reader: &mut R) -> io::Result<usize> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes) as usize)
+}
+
+pub(crate) fn read_i32<R: Read>(reader: &mut R) -> io::Result<i32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(i32::from_le_bytes(bytes))
+}
+
+pub(crate) fn read_scalar<R: Read, F: PrimeField>(reader: &mut R) -> io::Result<F> {
+    let mut repr = F::Repr::default();
+    reader.read_exact(repr.as_mut())?;
+    Option::from(F::from_repr(repr)).ok_or_else(|| invalid_data("Invalid scalar encoding"))
+}
+
+pub(crate) fn read_ec_point<R: Read, C: GroupEncoding>(reader: &mut R) -> io::Result<C> {
+    let mut repr = C::Repr::default();
+    reader.read_exact(repr.as_mut())?;
+    Option::from(C::from_bytes(&repr))
+        .ok_or_else(|| invalid_data("Invalid elliptic curve point encoding"))
+}
+
+pub(crate) fn read_vec<R: Read, T>(
+    reader: &mut R,
+    mut read: impl FnMut(&mut R) -> io::Result<T>,
+) -> io::Result<Vec<T>> {
+    let len = read_usize(reader)?;
+    (0..len).map(|_| read(reader)).collect()
+}